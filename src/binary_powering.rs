@@ -4,25 +4,26 @@ use cargo_snippet::snippet;
 /// `O(log n)`で累乗を求める
 ///
 /// modを取らないときはmに0を指定
+///
+/// modを取るときは`number_theory::pow_mod`で計算するので、`m`が大きくてもオーバーフローしない
 #[snippet]
 pub fn binary_powering(n: usize, r: usize, m: usize) -> usize {
+    if m != 0 {
+        return crate::number_theory::pow_mod(n as u64, r as u64, m as u64) as usize;
+    }
     let mut a = n;
     let mut n = r;
-    let mut res = 1usize;
-    let m = if m != 0 { Some(m) } else { None };
+    let mut res = 1;
 
     while n > 0 {
         if n & 1 != 0 {
             res *= a;
-            if let Some(m) = m {
-                res %= m
-            }
-        }
-        a *= a;
-        if let Some(m) = m {
-            a %= m;
         }
         n >>= 1;
+        // 最後の一回は二乗しない(使わない値でオーバーフローしないように)
+        if n > 0 {
+            a *= a;
+        }
     }
     res
 }
@@ -32,5 +33,9 @@ fn pow_test() {
     assert_eq!(9, binary_powering(3, 2, 10000));
     assert_eq!(1024, binary_powering(2, 10, 0));
     assert_eq!(1, binary_powering(100, 0, 0));
-    assert_eq!(1, binary_powering(10, 2, 3))
+    assert_eq!(1, binary_powering(10, 2, 3));
+    assert_eq!(1 << 40, binary_powering(2, 40, 0));
+    assert_eq!(0, binary_powering(5, 0, 1));
+    let m = 1_000_000_000_000_000_003;
+    assert_eq!(m - 1, binary_powering(m - 1, 3, m));
 }
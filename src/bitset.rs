@@ -4,9 +4,326 @@ pub use bitset::*;
 
 #[snippet("bitset", prefix = "pub use bitset::*;")]
 pub mod bitset {
-    pub type BitSet = Vec<bool>;
+    use crate::structure::algebraic_traits::abel::Abel;
+    use num_traits::One;
+    use std::fmt;
+    use std::ops::{
+        BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, Mul, Not, Shl,
+        ShlAssign, Shr, ShrAssign,
+    };
+
+    /// 固定長のbit列
+    ///
+    /// `u64`に64bitずつ詰めて持ち、bit演算やシフトを64bitまとめて行う
+    ///
+    /// `i`bit目は`words[i / 64]`の下から`i % 64`bit目
+    ///
+    /// 長さを超えた部分のbitは常に0に保つ
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+    pub struct BitSet {
+        len: usize,
+        words: Vec<u64>,
+    }
+
+    impl BitSet {
+        /// 長さを指定して、全て0で初期化
+        pub fn new(len: usize) -> Self {
+            Self {
+                len,
+                words: vec![0; (len + 63) / 64],
+            }
+        }
+
+        /// `n`の二進表記から構築する
+        ///
+        /// 長さは`n`のbit長(`n == 0`なら0)
+        pub fn from_usize(n: usize) -> Self {
+            let len = (0usize.count_zeros() - n.leading_zeros()) as usize;
+            let mut res = Self::new(len);
+            if len > 0 {
+                res.words[0] = n as u64;
+            }
+            res
+        }
+
+        /// `i`bit目が`a[i]`であるbit列
+        pub fn from_bools(a: &[bool]) -> Self {
+            let mut res = Self::new(a.len());
+            for (i, &b) in a.iter().enumerate() {
+                res.set(i, b);
+            }
+            res
+        }
+
+        /// `i`番目が`i`bit目であるような`Vec<bool>`
+        pub fn to_bools(&self) -> Vec<bool> {
+            (0..self.len).map(|i| self.get(i)).collect()
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// 長さを変える
+        ///
+        /// 伸ばした部分は0で埋められ、縮めた部分は捨てられる
+        pub fn resize(&mut self, len: usize) {
+            self.len = len;
+            self.words.resize((len + 63) / 64, 0);
+            self.trim();
+        }
+
+        /// 長さを超えた部分のbitを0にする
+        fn trim(&mut self) {
+            if self.len % 64 != 0 {
+                if let Some(last) = self.words.last_mut() {
+                    *last &= (1 << (self.len % 64)) - 1;
+                }
+            }
+        }
+
+        /// `i`bit目
+        pub fn get(&self, i: usize) -> bool {
+            assert!(i < self.len, "index out of range");
+            self.words[i / 64] >> (i % 64) & 1 == 1
+        }
+
+        /// `i`bit目を`b`にする
+        pub fn set(&mut self, i: usize, b: bool) {
+            assert!(i < self.len, "index out of range");
+            if b {
+                self.words[i / 64] |= 1 << (i % 64);
+            } else {
+                self.words[i / 64] &= !(1 << (i % 64));
+            }
+        }
+
+        /// `i`bit目を1にして、元の値を返す
+        pub fn put(&mut self, i: usize) -> bool {
+            let prev = self.get(i);
+            self.set(i, true);
+            prev
+        }
+
+        /// `i`bit目を反転する
+        pub fn toggle(&mut self, i: usize) {
+            assert!(i < self.len, "index out of range");
+            self.words[i / 64] ^= 1 << (i % 64);
+        }
+
+        /// 1の数
+        pub fn count_ones(&self) -> usize {
+            self.words.iter().map(|w| w.count_ones() as usize).sum()
+        }
+
+        /// 0の数
+        pub fn count_zeros(&self) -> usize {
+            self.len - self.count_ones()
+        }
+
+        /// 1である最小のbit
+        pub fn first_one(&self) -> Option<usize> {
+            self.find_one_from(0)
+        }
+
+        /// `i`より大きい、1である最小のbit
+        ///
+        /// `first_one`と合わせて、1であるbitを順に列挙できる
+        pub fn next_one(&self, i: usize) -> Option<usize> {
+            self.find_one_from(i + 1)
+        }
+
+        /// `i`以上の、1である最小のbit
+        fn find_one_from(&self, i: usize) -> Option<usize> {
+            if i >= self.len {
+                return None;
+            }
+            let mut w = i / 64;
+            let mut word = self.words[w] & (!0 << (i % 64));
+            loop {
+                if word != 0 {
+                    return Some(w * 64 + word.trailing_zeros() as usize);
+                }
+                w += 1;
+                if w == self.words.len() {
+                    return None;
+                }
+                word = self.words[w];
+            }
+        }
+
+        /// 上位bitから順に`0`と`1`で表した文字列
+        pub fn format(&self) -> String {
+            (0..self.len)
+                .rev()
+                .map(|i| if self.get(i) { '1' } else { '0' })
+                .collect()
+        }
+    }
+
+    impl fmt::Display for BitSet {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.format())
+        }
+    }
+
+    macro_rules! impl_bitset_ops {
+        ($($t:ident, $f:ident, $ta:ident, $fa:ident, $op:tt;)*) => {
+            $(
+                /// # Panic
+                /// 長さが異なるとき
+                impl<'a> $ta<&'a BitSet> for BitSet {
+                    fn $fa(&mut self, rhs: &'a BitSet) {
+                        assert_eq!(self.len, rhs.len, "length mismatch");
+                        for (x, y) in self.words.iter_mut().zip(rhs.words.iter()) {
+                            *x $op *y;
+                        }
+                    }
+                }
+
+                impl $ta for BitSet {
+                    fn $fa(&mut self, rhs: BitSet) {
+                        *self $op &rhs;
+                    }
+                }
+
+                impl<'a> $t<&'a BitSet> for &'a BitSet {
+                    type Output = BitSet;
+                    fn $f(self, rhs: &'a BitSet) -> BitSet {
+                        let mut res = self.clone();
+                        res $op rhs;
+                        res
+                    }
+                }
+
+                impl $t for BitSet {
+                    type Output = BitSet;
+                    fn $f(mut self, rhs: BitSet) -> BitSet {
+                        self $op &rhs;
+                        self
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_bitset_ops!(
+        BitAnd, bitand, BitAndAssign, bitand_assign, &=;
+        BitOr, bitor, BitOrAssign, bitor_assign, |=;
+        BitXor, bitxor, BitXorAssign, bitxor_assign, ^=;
+    );
+
+    impl Not for BitSet {
+        type Output = BitSet;
+        fn not(mut self) -> BitSet {
+            for w in self.words.iter_mut() {
+                *w = !*w;
+            }
+            self.trim();
+            self
+        }
+    }
+
+    impl<'a> Not for &'a BitSet {
+        type Output = BitSet;
+        fn not(self) -> BitSet {
+            !self.clone()
+        }
+    }
+
+    /// 上位bitの方向へずらす、長さは変わらない
+    impl ShlAssign<usize> for BitSet {
+        #[allow(clippy::suspicious_op_assign_impl)]
+        fn shl_assign(&mut self, rhs: usize) {
+            let n = self.words.len();
+            let (ws, bs) = (rhs / 64, rhs % 64);
+            for i in (0..n).rev() {
+                self.words[i] = if i < ws {
+                    0
+                } else if bs == 0 || i == ws {
+                    self.words[i - ws] << bs
+                } else {
+                    self.words[i - ws] << bs | self.words[i - ws - 1] >> (64 - bs)
+                };
+            }
+            self.trim();
+        }
+    }
+
+    /// 下位bitの方向へずらす、長さは変わらない
+    impl ShrAssign<usize> for BitSet {
+        #[allow(clippy::suspicious_op_assign_impl)]
+        fn shr_assign(&mut self, rhs: usize) {
+            let n = self.words.len();
+            let (ws, bs) = (rhs / 64, rhs % 64);
+            for i in 0..n {
+                self.words[i] = if i + ws >= n {
+                    0
+                } else if bs == 0 || i + ws + 1 == n {
+                    self.words[i + ws] >> bs
+                } else {
+                    self.words[i + ws] >> bs | self.words[i + ws + 1] << (64 - bs)
+                };
+            }
+        }
+    }
+
+    impl Shl<usize> for BitSet {
+        type Output = BitSet;
+        fn shl(mut self, rhs: usize) -> BitSet {
+            self <<= rhs;
+            self
+        }
+    }
+
+    impl<'a> Shl<usize> for &'a BitSet {
+        type Output = BitSet;
+        fn shl(self, rhs: usize) -> BitSet {
+            self.clone() << rhs
+        }
+    }
+
+    impl Shr<usize> for BitSet {
+        type Output = BitSet;
+        fn shr(mut self, rhs: usize) -> BitSet {
+            self >>= rhs;
+            self
+        }
+    }
+
+    impl<'a> Shr<usize> for &'a BitSet {
+        type Output = BitSet;
+        fn shr(self, rhs: usize) -> BitSet {
+            self.clone() >> rhs
+        }
+    }
+
+    /// 部分和問題
+    ///
+    /// `weights`の部分集合の和として`limit`以下のどの値が作れるか
+    ///
+    /// 返り値の`s`bit目が1なら`s`が作れる
+    ///
+    /// `O(n * limit / 64)`
+    pub fn subset_sum(weights: &[usize], limit: usize) -> BitSet {
+        let mut dp = BitSet::new(limit + 1);
+        dp.set(0, true);
+        for &w in weights {
+            if w <= limit {
+                let shifted = &dp << w;
+                dp |= shifted;
+            }
+        }
+        dp
+    }
 
     /// bitの数を持つ
+    ///
+    /// 生成する`BitSet`は`get(0)`が最下位bit(以前の`Vec<bool>`とは逆順、`IntoIterBitSet`を参照)
     #[derive(Debug, Copy, Clone)]
     pub struct BitSetsGen {
         size: usize,
@@ -46,16 +363,15 @@ pub mod bitset {
     ///
     /// 保持するのはサイズと次に生成する数字だけ
     ///
-    /// Iteratorは長さ`2 ^ size`で、
-    /// はじめは`[false, false, ..., false, false]`から始まり、
+    /// Iteratorは長さ`2 ^ size`で、`0`から`2 ^ size - 1`までの二進表記を順に生成する
     ///
-    /// `[false, false, ..., false, true]`
+    /// はじめは`000...00`から始まり、`000...01`、`000...10`、`000...11`のように続く。
     ///
-    /// `[false, false, ..., true, false]`
+    /// 最後は`111...11`である。
     ///
-    /// `[false, false, ..., true, true]`のように右詰で続く。
-    ///
-    /// 最後は`[true, true, ..., true, true]`である。
+    /// 生成するのは`BitSet`で、`get(0)`が最下位bitである。
+    /// 以前は`Vec<bool>`を生成していて`b[0]`が最上位bitだったので、
+    /// `b[i]`で読んでいたコードは`b.get(size - 1 - i)`に書き換える必要がある。
     #[derive(Copy, Clone, Debug)]
     pub struct IntoIterBitSet {
         size: usize,
@@ -68,21 +384,21 @@ pub mod bitset {
             if self.current == (1 << self.size) {
                 None
             } else {
-                let res = Some(
-                    (0..self.size)
-                        .rev()
-                        .map(|x| self.current & (1 << x) != 0)
-                        .collect::<Vec<_>>(),
-                );
+                let mut res = BitSet::new(self.size);
+                if self.size > 0 {
+                    res.words[0] = self.current as u64;
+                }
                 self.current += 1;
-                res
+                Some(res)
             }
         }
     }
 
     #[test]
     fn bs_iter() {
-        let mut bs = BitSetsGen::new(3).into_iter();
+        // 以前の`Vec<bool>`(0番目が最上位bit)と同じ並びになることを確かめる
+        let msb_first = |b: BitSet| (0..b.len()).rev().map(|i| b.get(i)).collect::<Vec<_>>();
+        let mut bs = BitSetsGen::new(3).into_iter().map(msb_first);
 
         assert_eq!(Some(vec![false, false, false]), bs.next());
         assert_eq!(Some(vec![false, false, true]), bs.next());
@@ -93,97 +409,451 @@ pub mod bitset {
         assert_eq!(Some(vec![true, true, false]), bs.next());
         assert_eq!(Some(vec![true, true, true]), bs.next());
         assert_eq!(None, bs.next());
+
+        let b = BitSetsGen::new(3).into_iter().nth(6).unwrap();
+        assert_eq!(b.format(), "110".to_string());
+        assert_eq!(b.to_bools(), vec![false, true, true]);
+        assert!(!b.get(0) && b.get(1) && b.get(2));
+    }
+
+    #[test]
+    fn construct_from_int() {
+        // 以前の`Vec<bool>`(0番目が最上位bit)と同じ値になることを確かめる
+        let msb_first = |b: &BitSet| (0..b.len()).rev().map(|i| b.get(i)).collect::<Vec<_>>();
+
+        let a = 10;
+        let b = BitSet::from_usize(a);
+        assert_eq!(msb_first(&b), vec![true, false, true, false]);
+        assert_eq!(b.to_bools(), vec![false, true, false, true]);
+
+        let a = 16;
+        let b = BitSet::from_usize(a);
+        assert_eq!(msb_first(&b), vec![true, false, false, false, false]);
+
+        let a = 15;
+        let mut b = BitSet::from_usize(a);
+        assert_eq!(msb_first(&b), vec![true; 4]);
+
+        assert_eq!(b.count_zeros(), 0);
+
+        // 以前の`grow(3)`
+        b.resize(7);
+
+        assert_eq!(b.count_zeros(), 3);
+
+        // 以前の`set(1, true)`
+        b.set(5, true);
+
+        assert_eq!(
+            msb_first(&b),
+            vec![false, true, false, true, true, true, true]
+        );
+
+        // 以前の`shr(2)`
+        b >>= 2;
+
+        assert_eq!(
+            msb_first(&b),
+            vec![false, false, false, true, false, true, true]
+        );
+
+        assert_eq!(b.format(), "0001011".to_string());
+        assert_eq!(b.to_string(), "0001011".to_string());
+    }
+
+    #[test]
+    fn bitset_ops_test() {
+        // 64bitをまたぐ長さで、Vec<bool>での愚直と比較する
+        let n = 150;
+        let a = (0..n).map(|i| i % 3 == 0 || i % 7 == 1).collect::<Vec<_>>();
+        let b = (0..n).map(|i| i % 5 == 2).collect::<Vec<_>>();
+        let (x, y) = (BitSet::from_bools(&a), BitSet::from_bools(&b));
+
+        let zip = |f: &dyn Fn(bool, bool) -> bool| {
+            a.iter()
+                .zip(b.iter())
+                .map(|(&p, &q)| f(p, q))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!((&x & &y).to_bools(), zip(&|p, q| p & q));
+        assert_eq!((&x | &y).to_bools(), zip(&|p, q| p | q));
+        assert_eq!((x.clone() ^ y.clone()).to_bools(), zip(&|p, q| p ^ q));
+        assert_eq!((!&x).to_bools(), a.iter().map(|&p| !p).collect::<Vec<_>>());
+        assert_eq!((!&x).count_ones(), x.count_zeros());
+
+        for &k in &[0, 1, 63, 64, 65, 100, 149, 150, 200] {
+            let shl = (0..n).map(|i| i >= k && a[i - k]).collect::<Vec<_>>();
+            let shr = (0..n).map(|i| i + k < n && a[i + k]).collect::<Vec<_>>();
+            assert_eq!((&x << k).to_bools(), shl);
+            assert_eq!((&x >> k).to_bools(), shr);
+        }
+
+        let mut ones = Vec::new();
+        let mut cur = y.first_one();
+        while let Some(i) = cur {
+            ones.push(i);
+            cur = y.next_one(i);
+        }
+        assert_eq!(ones, (0..n).filter(|&i| b[i]).collect::<Vec<_>>());
+        assert_eq!(BitSet::new(70).first_one(), None);
+        assert_eq!(y.count_ones(), ones.len());
     }
 
-    pub trait BitsOps {
-        fn new(n: usize) -> Self;
-        fn count_zeros(&self) -> usize;
-        fn count_ones(&self) -> usize;
-        fn grow(&mut self, bits: usize);
-        fn put(&mut self, bits: usize) -> bool;
-        fn toggle(&mut self, bit: usize);
-        fn set(&mut self, bit: usize, into: bool);
-        fn shl(&mut self, rhs: usize);
-        fn shr(&mut self, rhs: usize);
-        fn format(&self) -> String;
+    #[test]
+    fn subset_sum_test() {
+        let weights = [3, 5, 7, 100];
+        let dp = subset_sum(&weights, 20);
+        let expected = (0..=20)
+            .map(|s| {
+                (0..1 << weights.len()).any(|mask: usize| {
+                    (0..weights.len())
+                        .filter(|&i| mask >> i & 1 == 1)
+                        .map(|i| weights[i])
+                        .sum::<usize>()
+                        == s
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(dp.to_bools(), expected);
     }
 
-    impl BitsOps for BitSet {
-        fn new(n: usize) -> Self {
-            let mut b = 1;
-            let mut res = Vec::new();
-            while b <= n {
-                res.push(b & n != 0);
-                b <<= 1;
-            }
+    /// `mask`の部分集合を降順に列挙するIterator
+    ///
+    /// `mask`自身から始まり、最後は`0`
+    #[derive(Copy, Clone, Debug)]
+    pub struct Submasks {
+        mask: usize,
+        current: Option<usize>,
+    }
 
-            res.reverse();
-            res
+    impl Iterator for Submasks {
+        type Item = usize;
+        fn next(&mut self) -> Option<usize> {
+            let res = self.current?;
+            self.current = if res == 0 {
+                None
+            } else {
+                Some((res - 1) & self.mask)
+            };
+            Some(res)
         }
-        fn count_zeros(&self) -> usize {
-            self.iter().filter(|x| !**x).count()
+    }
+
+    /// `mask`の部分集合を降順に列挙する
+    ///
+    /// 全ての`mask`についての合計で`O(3^n)`
+    pub fn submasks(mask: usize) -> Submasks {
+        Submasks {
+            mask,
+            current: Some(mask),
         }
-        fn count_ones(&self) -> usize {
-            self.iter().filter(|x| **x).count()
+    }
+
+    /// `n`bitの範囲で`mask`を含む集合を昇順に列挙するIterator
+    #[derive(Copy, Clone, Debug)]
+    pub struct Supersets {
+        mask: usize,
+        end: usize,
+        current: usize,
+    }
+
+    impl Iterator for Supersets {
+        type Item = usize;
+        fn next(&mut self) -> Option<usize> {
+            if self.current >= self.end {
+                return None;
+            }
+            let res = self.current;
+            self.current = (res + 1) | self.mask;
+            Some(res)
         }
-        fn grow(&mut self, bits: usize) {
-            self.reverse();
-            self.append(&mut vec![false; bits]);
-            self.reverse();
+    }
+
+    /// `n`bitの範囲で`mask`を含む集合を昇順に列挙する
+    ///
+    /// `mask`自身から始まり、最後は`2^n - 1`
+    pub fn supersets(mask: usize, n: usize) -> Supersets {
+        Supersets {
+            mask,
+            end: 1 << n,
+            current: mask,
         }
-        fn put(&mut self, bits: usize) -> bool {
-            let prev = self[bits];
-            self[bits] = true;
-            prev
+    }
+
+    /// `n`bitのうち、ちょうど`k`bitが立った集合を昇順に列挙するIterator
+    ///
+    /// Gosper's hackで次の集合を求める
+    #[derive(Copy, Clone, Debug)]
+    pub struct KSubsets {
+        end: usize,
+        current: usize,
+    }
+
+    impl Iterator for KSubsets {
+        type Item = usize;
+        fn next(&mut self) -> Option<usize> {
+            if self.current >= self.end {
+                return None;
+            }
+            let res = self.current;
+            if res == 0 {
+                self.current = self.end;
+            } else {
+                let lowest = res & res.wrapping_neg();
+                let carried = res + lowest;
+                self.current = (((res & !carried) / lowest) >> 1) | carried;
+            }
+            Some(res)
         }
-        fn toggle(&mut self, bit: usize) {
-            self[bit] = !self[bit];
+    }
+
+    /// `n`bitのうち、ちょうど`k`bitが立った集合を昇順に列挙する
+    ///
+    /// `k > n`のときは何も生成しない
+    pub fn k_subsets(n: usize, k: usize) -> KSubsets {
+        KSubsets {
+            end: 1 << n,
+            current: if k > n { 1 << n } else { (1 << k) - 1 },
         }
-        fn set(&mut self, bit: usize, into: bool) {
-            self[bit] = into;
+    }
+
+    /// 部分集合についてのゼータ変換(in-place)
+    ///
+    /// `f[S] = Σ_{T ⊆ S} a[T]`
+    ///
+    /// `a.len()`は2冪である必要がある
+    ///
+    /// `O(n 2^n)`
+    pub fn subset_zeta<T: Abel + Copy>(a: &mut [T]) {
+        assert!(a.len().is_power_of_two(), "length must be a power of two");
+        let mut bit = 1;
+        while bit < a.len() {
+            for s in 0..a.len() {
+                if s & bit != 0 {
+                    a[s] = T::op(&a[s], &a[s ^ bit]);
+                }
+            }
+            bit <<= 1;
         }
-        fn shl(&mut self, rhs: usize) {
-            self.append(&mut vec![false; rhs]);
+    }
+
+    /// 部分集合についてのメビウス変換(in-place)、`subset_zeta`の逆変換
+    ///
+    /// `O(n 2^n)`
+    pub fn subset_mobius<T: Abel + Copy>(a: &mut [T]) {
+        assert!(a.len().is_power_of_two(), "length must be a power of two");
+        let mut bit = 1;
+        while bit < a.len() {
+            for s in 0..a.len() {
+                if s & bit != 0 {
+                    a[s] = T::inverse(&a[s], &a[s ^ bit]);
+                }
+            }
+            bit <<= 1;
         }
-        fn shr(&mut self, rhs: usize) {
-            let len = self.len();
-            let mut res = vec![false; rhs];
-            res.append(&mut self[..len - rhs].to_vec());
-            *self = res;
+    }
+
+    /// 上位集合についてのゼータ変換(in-place)
+    ///
+    /// `f[S] = Σ_{S ⊆ T} a[T]`
+    ///
+    /// `O(n 2^n)`
+    pub fn superset_zeta<T: Abel + Copy>(a: &mut [T]) {
+        assert!(a.len().is_power_of_two(), "length must be a power of two");
+        let mut bit = 1;
+        while bit < a.len() {
+            for s in 0..a.len() {
+                if s & bit == 0 {
+                    a[s] = T::op(&a[s], &a[s | bit]);
+                }
+            }
+            bit <<= 1;
         }
-        fn format(&self) -> String {
-            self.iter().map(|x| if *x { '1' } else { '0' }).collect()
+    }
+
+    /// 上位集合についてのメビウス変換(in-place)、`superset_zeta`の逆変換
+    ///
+    /// `O(n 2^n)`
+    pub fn superset_mobius<T: Abel + Copy>(a: &mut [T]) {
+        assert!(a.len().is_power_of_two(), "length must be a power of two");
+        let mut bit = 1;
+        while bit < a.len() {
+            for s in 0..a.len() {
+                if s & bit == 0 {
+                    a[s] = T::inverse(&a[s], &a[s | bit]);
+                }
+            }
+            bit <<= 1;
         }
     }
 
-    #[test]
-    fn construct_from_int() {
-        let a = 10;
-        let b: BitSet = BitsOps::new(a);
-        assert_eq!(b, vec![true, false, true, false]);
+    /// 正規化しないアダマール変換(in-place)
+    ///
+    /// 二回かけると`a.len()`倍になる
+    ///
+    /// `O(n 2^n)`
+    pub fn walsh_hadamard<T: Abel + Copy>(a: &mut [T]) {
+        assert!(a.len().is_power_of_two(), "length must be a power of two");
+        let mut bit = 1;
+        while bit < a.len() {
+            for s in 0..a.len() {
+                if s & bit == 0 {
+                    let (u, v) = (a[s], a[s | bit]);
+                    a[s] = T::op(&u, &v);
+                    a[s | bit] = T::inverse(&u, &v);
+                }
+            }
+            bit <<= 1;
+        }
+    }
 
-        let a = 16;
-        let b: BitSet = BitsOps::new(a);
-        assert_eq!(b, vec![true, false, false, false, false]);
+    /// 二つの列を同じ変換で移して、各点積を取って逆変換する
+    fn transform_convolution<T: Copy + Mul<Output = T>>(
+        a: &[T],
+        b: &[T],
+        transform: fn(&mut [T]),
+        inverse: fn(&mut [T]),
+    ) -> Vec<T> {
+        assert_eq!(a.len(), b.len(), "length mismatch");
+        let mut fa = a.to_vec();
+        let mut fb = b.to_vec();
+        transform(&mut fa);
+        transform(&mut fb);
+        for (x, &y) in fa.iter_mut().zip(fb.iter()) {
+            *x = *x * y;
+        }
+        inverse(&mut fa);
+        fa
+    }
 
-        let a = 15;
-        let mut b: BitSet = BitsOps::new(a);
-        assert_eq!(b, vec![true; 4]);
+    /// OR畳み込み
+    ///
+    /// `c[k] = Σ_{i | j = k} a[i] * b[j]`
+    ///
+    /// `a.len() == b.len()`で、2冪である必要がある
+    ///
+    /// `O(n 2^n)`
+    pub fn or_convolution<T: Abel + Copy + Mul<Output = T>>(a: &[T], b: &[T]) -> Vec<T> {
+        transform_convolution(a, b, subset_zeta, subset_mobius)
+    }
 
-        assert_eq!(b.count_zeros(), 0);
+    /// AND畳み込み
+    ///
+    /// `c[k] = Σ_{i & j = k} a[i] * b[j]`
+    ///
+    /// `O(n 2^n)`
+    pub fn and_convolution<T: Abel + Copy + Mul<Output = T>>(a: &[T], b: &[T]) -> Vec<T> {
+        transform_convolution(a, b, superset_zeta, superset_mobius)
+    }
 
-        b.grow(3);
+    /// XOR畳み込み
+    ///
+    /// `c[k] = Σ_{i ^ j = k} a[i] * b[j]`
+    ///
+    /// 逆変換で`a.len()`で割るため、modは奇数である必要がある
+    ///
+    /// `O(n 2^n)`
+    pub fn xor_convolution<T: Abel + Copy + One + Mul<Output = T> + Div<Output = T>>(
+        a: &[T],
+        b: &[T],
+    ) -> Vec<T> {
+        let res = transform_convolution(a, b, walsh_hadamard, walsh_hadamard);
+        // `a.len()`を`1 + 1`の積で作る
+        let two = T::op(&T::one(), &T::one());
+        let mut n = T::one();
+        let mut len = 1;
+        while len < res.len() {
+            n = n * two;
+            len <<= 1;
+        }
+        res.into_iter().map(|x| x / n).collect()
+    }
 
-        assert_eq!(b.count_zeros(), 3);
+    #[test]
+    fn subset_enumeration_test() {
+        assert_eq!(
+            submasks(0b1010).collect::<Vec<_>>(),
+            vec![0b1010, 0b1000, 0b0010, 0]
+        );
+        assert_eq!(submasks(0).collect::<Vec<_>>(), vec![0]);
 
-        b.set(1, true);
+        assert_eq!(
+            supersets(0b0101, 4).collect::<Vec<_>>(),
+            vec![0b0101, 0b0111, 0b1101, 0b1111]
+        );
+        assert_eq!(supersets(0, 2).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
 
-        assert_eq!(b, vec![false, true, false, true, true, true, true]);
+        for n in 0..8 {
+            for k in 0..=n + 1 {
+                let expected = (0..1usize << n)
+                    .filter(|s| s.count_ones() as usize == k)
+                    .collect::<Vec<_>>();
+                assert_eq!(k_subsets(n, k).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
 
-        b.shr(2);
+    #[test]
+    fn zeta_mobius_test() {
+        let n = 4;
+        let a = (0..1 << n)
+            .map(|i| (i * 37 % 11) as i64 - 5)
+            .collect::<Vec<_>>();
 
-        assert_eq!(b, vec![false, false, false, true, false, true, true]);
+        let mut sub = a.clone();
+        subset_zeta(&mut sub);
+        let mut sup = a.clone();
+        superset_zeta(&mut sup);
+        for s in 0..1 << n {
+            let sum_sub: i64 = submasks(s).map(|t| a[t]).sum();
+            let sum_sup: i64 = supersets(s, n).map(|t| a[t]).sum();
+            assert_eq!(sub[s], sum_sub);
+            assert_eq!(sup[s], sum_sup);
+        }
+        subset_mobius(&mut sub);
+        superset_mobius(&mut sup);
+        assert_eq!(sub, a);
+        assert_eq!(sup, a);
+    }
 
-        assert_eq!(b.format(), "0001011".to_string());
+    #[test]
+    fn bitwise_convolution_test() {
+        use crate::static_modint::ModInt998244353 as Mint;
+
+        let n = 3;
+        let a = (0..1 << n)
+            .map(|i| Mint::new(i * i + 1))
+            .collect::<Vec<_>>();
+        let b = (0..1 << n)
+            .map(|i| Mint::new(7 - 2 * i))
+            .collect::<Vec<_>>();
+        let naive = |f: &dyn Fn(usize, usize) -> usize| {
+            let mut res = vec![Mint::new(0); 1 << n];
+            for i in 0..1 << n {
+                for j in 0..1 << n {
+                    res[f(i, j)] += a[i] * b[j];
+                }
+            }
+            res
+        };
+        assert_eq!(or_convolution(&a, &b), naive(&|i, j| i | j));
+        assert_eq!(and_convolution(&a, &b), naive(&|i, j| i & j));
+        assert_eq!(xor_convolution(&a, &b), naive(&|i, j| i ^ j));
+
+        // ModIntでも使える
+        let m = 1_000_000_007;
+        let a = (0..4)
+            .map(|i| crate::modint::ModInt::new(i + 1, m))
+            .collect::<Vec<_>>();
+        let c = or_convolution(&a, &a);
+        assert_eq!(
+            c.iter().map(|x| x.get()).collect::<Vec<_>>(),
+            vec![1, 8, 15, 76]
+        );
+        let c = xor_convolution(&a, &a);
+        assert_eq!(
+            c.iter().map(|x| x.get()).collect::<Vec<_>>(),
+            vec![30, 28, 22, 20]
+        );
     }
 }
@@ -1,17 +1,25 @@
 pub use binomial_coefficient::*;
 
 pub mod binomial_coefficient {
+    use crate::crt::crt;
     use crate::modint::{IntoModInt, ModInt};
+    use crate::number_theory::{inv_mod, pow_mod};
+    use crate::prime::trial_division::factorization;
+    use crate::static_modint::ModIntBase;
     use num_traits::Zero;
     use std::num::NonZeroU32;
+    use std::ops::Mul;
 
     pub trait PartialBinomialCoefficient {
-        fn partial_binomial(&self, n: usize, k: usize) -> Option<ModInt>;
+        /// テーブルが返す値の型
+        type Output;
+
+        fn partial_binomial(&self, n: usize, k: usize) -> Option<Self::Output>;
     }
 
     pub trait BinomialCoefficient: PartialBinomialCoefficient {
         /// `n C k`
-        fn binomial(&self, n: usize, k: usize) -> ModInt {
+        fn binomial(&self, n: usize, k: usize) -> Self::Output {
             self.partial_binomial(n, k).unwrap()
         }
     }
@@ -22,17 +30,20 @@ pub mod binomial_coefficient {
     /// factrial = [1, 1, 2, 6, 24, 120, ...],
     ///
     /// `1 <= k <= n <= 10^7` 程度
-    pub struct BCTDP {
+    ///
+    /// 要素の型は`ModInt`の他に、`ModIntBase`を実装した
+    /// `StaticModInt`や`DynamicModInt`も使える
+    pub struct BCTDP<T = ModInt> {
         _modulo: NonZeroU32,
         // `factorial[i]` = iの階乗
-        factorial: Vec<ModInt>,
+        factorial: Vec<T>,
         // `inv[i]` = iの逆元
-        inverse: Vec<ModInt>,
+        inverse: Vec<T>,
         // `factorial_inverse[i]` = iの階乗の逆元
-        factorial_inverse: Vec<ModInt>,
+        factorial_inverse: Vec<T>,
     }
 
-    impl BCTDP {
+    impl BCTDP<ModInt> {
         /// 初期化
         ///
         /// DPを用いて `O(n log m)`
@@ -60,36 +71,73 @@ pub mod binomial_coefficient {
                 factorial_inverse,
             }
         }
+    }
+
+    impl<T: ModIntBase> BCTDP<T> {
+        /// modを型(またはスレッドローカルな設定)から取って初期化
+        ///
+        /// `O(n)`
+        ///
+        /// # Panic
+        /// `n >= T::modulus()`のとき
+        pub fn with_modint(n: usize) -> Self {
+            let modulo = T::modulus() as usize;
+            assert!(n < modulo, "n must be less than modulo");
+
+            let mut factorial = vec![T::one(); 2];
+            factorial.reserve_exact(n);
+            let mut inverse = vec![T::zero(), T::one()];
+            inverse.reserve_exact(n);
+            let mut factorial_inverse = vec![T::one(); 2];
+            factorial_inverse.reserve_exact(n);
+
+            for i in 2..=n {
+                factorial.push(factorial[i - 1] * T::from_u64(i as u64));
+                inverse.push(-(inverse[modulo % i] * T::from_u64((modulo / i) as u64)));
+                factorial_inverse.push(factorial_inverse[i - 1] * inverse[i]);
+            }
+
+            Self {
+                _modulo: NonZeroU32::new(modulo as u32).unwrap(),
+                factorial,
+                inverse,
+                factorial_inverse,
+            }
+        }
+    }
 
+    impl<T: Copy> BCTDP<T> {
         pub fn get_mod(&self) -> usize {
             self._modulo.get() as usize
         }
 
-        pub fn factorial(&self, n: usize) -> ModInt {
+        pub fn factorial(&self, n: usize) -> T {
             self.factorial[n]
         }
 
-        pub fn factorial_inverse(&self, n: usize) -> ModInt {
+        pub fn factorial_inverse(&self, n: usize) -> T {
             self.factorial_inverse[n]
         }
 
         /// `n` の mod self._modulo における逆元
-        pub fn inv(&self, n: usize) -> ModInt {
+        pub fn inv(&self, n: usize) -> T {
             self.inverse[n]
         }
     }
 
-    impl PartialBinomialCoefficient for BCTDP {
-        fn partial_binomial(&self, n: usize, k: usize) -> Option<ModInt> {
+    impl<T: Copy + Zero + Mul<Output = T>> PartialBinomialCoefficient for BCTDP<T> {
+        type Output = T;
+
+        fn partial_binomial(&self, n: usize, k: usize) -> Option<T> {
             Some(if n < k {
-                ModInt::zero()
+                T::zero()
             } else {
                 self.factorial[n] * self.factorial_inverse[k] * self.factorial_inverse[n - k]
             })
         }
     }
 
-    impl BinomialCoefficient for BCTDP {}
+    impl<T: Copy + Zero + Mul<Output = T>> BinomialCoefficient for BCTDP<T> {}
 
     #[test]
     fn binomial_dp() {
@@ -106,6 +154,26 @@ pub mod binomial_coefficient {
         assert_eq!(tbl.factorial(8).get(), 120 * 6 * 7 * 8);
     }
 
+    #[test]
+    fn bct_modint_base_test() {
+        use crate::dynamic_modint::DynModInt;
+        use crate::static_modint::ModInt1000000007;
+
+        let tbl = BCTDP::<ModInt1000000007>::with_modint(100);
+        assert_eq!(tbl.binomial(10, 6).val(), 210);
+        assert_eq!(tbl.factorial(5).val(), 120);
+        assert_eq!((tbl.inv(7) * ModInt1000000007::new(7)).val(), 1);
+        assert_eq!(tbl.binomial(3, 5).val(), 0);
+
+        DynModInt::set_modulus(13);
+        let tbl = BCTDP::<DynModInt>::with_modint(12);
+        assert_eq!(tbl.get_mod(), 13);
+        assert_eq!(tbl.binomial(12, 4).val(), (495 % 13) as u32);
+        for i in 1..=12 {
+            assert_eq!((tbl.inv(i) * DynModInt::new(i as i64)).val(), 1);
+        }
+    }
+
     /// `n` が固定値のときに有効
     /// `(n(固定値), mod, _[i] = n C i)`
     ///
@@ -130,6 +198,8 @@ pub mod binomial_coefficient {
     }
 
     impl PartialBinomialCoefficient for BCTholdN {
+        type Output = ModInt;
+
         /// #Panic
         ///
         /// self.0 == _n でないとき
@@ -187,6 +257,8 @@ pub mod binomial_coefficient {
     }
 
     impl PartialBinomialCoefficient for BCTSmallNK {
+        type Output = ModInt;
+
         fn partial_binomial(&self, n: usize, k: usize) -> Option<ModInt> {
             if n > self.size() || k > self.size() {
                 panic!("n or k is too large, compere to dp table!",)
@@ -202,4 +274,258 @@ pub mod binomial_coefficient {
         let tbl = BCTSmallNK::new(100, 1000000007);
         assert_eq!(tbl.binomial(4, 2).get(), 6);
     }
+
+    /// Lucasの定理で、素数`p`を法とした`n C k`を求める
+    ///
+    /// `n, k`を`p`進展開した各桁の二項係数の積になる
+    ///
+    /// 初期化: `O(p)`、クエリ: `O(log_p n)`
+    ///
+    /// `n, k`はいくらでも大きくてよいが、`p <= 10^7` 程度
+    pub struct BCTLucas {
+        table: BCTDP,
+    }
+
+    impl BCTLucas {
+        /// # Panic
+        /// `p < 2`のとき(`p`が素数でなければ結果は正しくない)
+        pub fn new(p: usize) -> Self {
+            assert!(p >= 2, "modulo number must be a prime");
+            Self {
+                table: BCTDP::new(p - 1, p),
+            }
+        }
+
+        pub fn get_mod(&self) -> usize {
+            self.table.get_mod()
+        }
+    }
+
+    impl PartialBinomialCoefficient for BCTLucas {
+        type Output = ModInt;
+
+        fn partial_binomial(&self, mut n: usize, mut k: usize) -> Option<ModInt> {
+            let p = self.get_mod();
+            let mut res = ModInt::new(1, p);
+            while k > 0 {
+                let (ni, ki) = (n % p, k % p);
+                if ni < ki {
+                    return Some(ModInt::new(0, p));
+                }
+                res *= self.table.binomial(ni, ki);
+                n /= p;
+                k /= p;
+            }
+            Some(res)
+        }
+    }
+
+    impl BinomialCoefficient for BCTLucas {}
+
+    /// 素数冪`p^e`を法とした`n C k`を求める(Granvilleによる一般化)
+    ///
+    /// `n!`から`p`を除いた部分を、`p^e`を周期とする積の繰り返しとして計算する
+    ///
+    /// 初期化: `O(p^e)`、クエリ: `O(log n)`
+    ///
+    /// `n, k`はいくらでも大きくてよいが、`p^e <= 10^7` 程度
+    pub struct BCTPrimePower {
+        p: usize,
+        e: usize,
+        pe: usize,
+        /// `coprime_factorial[i]` = `1..=i`のうち`p`と互いに素なものの積 (mod p^e)
+        coprime_factorial: Vec<usize>,
+    }
+
+    impl BCTPrimePower {
+        /// # Panic
+        /// `p < 2 || e < 1`のとき(`p`が素数でなければ結果は正しくない)
+        pub fn new(p: usize, e: usize) -> Self {
+            assert!(p >= 2 && e >= 1, "modulo number must be a prime power");
+            let pe = p.pow(e as u32);
+            let mut coprime_factorial = vec![1 % pe; pe];
+            for i in 1..pe {
+                let mul = if i % p == 0 { 1 } else { i };
+                coprime_factorial[i] = coprime_factorial[i - 1] * mul % pe;
+            }
+            Self {
+                p,
+                e,
+                pe,
+                coprime_factorial,
+            }
+        }
+
+        /// `p^e`
+        pub fn get_mod(&self) -> usize {
+            self.pe
+        }
+
+        /// `n!`を割り切る`p`の指数
+        fn legendre(&self, mut n: usize) -> usize {
+            let mut res = 0;
+            while n > 0 {
+                n /= self.p;
+                res += n;
+            }
+            res
+        }
+
+        /// `n!`から素因数`p`を全て取り除いた値 (mod p^e)
+        fn factorial_without_p(&self, mut n: usize) -> usize {
+            let pe = self.pe as u64;
+            let period = self.coprime_factorial[self.pe - 1] as u64;
+            let mut res = 1 % pe;
+            while n > 0 {
+                res = res * pow_mod(period, (n / self.pe) as u64, pe) % pe;
+                res = res * self.coprime_factorial[n % self.pe] as u64 % pe;
+                n /= self.p;
+            }
+            res as usize
+        }
+    }
+
+    impl PartialBinomialCoefficient for BCTPrimePower {
+        type Output = ModInt;
+
+        fn partial_binomial(&self, n: usize, k: usize) -> Option<ModInt> {
+            if n < k {
+                return Some(ModInt::new(0, self.pe));
+            }
+            let v = self.legendre(n) - self.legendre(k) - self.legendre(n - k);
+            if v >= self.e {
+                return Some(ModInt::new(0, self.pe));
+            }
+            let pe = self.pe as i64;
+            let inv = |x: usize| inv_mod(x as i64, pe).unwrap();
+            let res = self.factorial_without_p(n) as i64 * inv(self.factorial_without_p(k)) % pe
+                * inv(self.factorial_without_p(n - k))
+                % pe
+                * self.p.pow(v as u32) as i64;
+            Some(ModInt::new(res, self.pe))
+        }
+    }
+
+    impl BinomialCoefficient for BCTPrimePower {}
+
+    /// 任意の`m`を法とした`n C k`を求める
+    ///
+    /// `m`を素因数分解して、各素数冪での値を中国剰余定理でまとめる
+    ///
+    /// 初期化: `O(√m + Σ p^e)`、クエリ: `O(ω(m) log n)`
+    ///
+    /// `n, k`はいくらでも大きくてよいが、`m <= 10^7` 程度
+    pub struct BCTArbitraryMod {
+        modulo: usize,
+        parts: Vec<BCTPrimePower>,
+    }
+
+    impl BCTArbitraryMod {
+        /// # Panic
+        /// `m < 1`のとき
+        pub fn new(m: usize) -> Self {
+            assert!(m >= 1, "modulo number must be positive");
+            Self {
+                modulo: m,
+                parts: factorization(m)
+                    .into_iter()
+                    .map(|(p, e)| BCTPrimePower::new(p, e))
+                    .collect(),
+            }
+        }
+
+        pub fn get_mod(&self) -> usize {
+            self.modulo
+        }
+    }
+
+    impl PartialBinomialCoefficient for BCTArbitraryMod {
+        type Output = ModInt;
+
+        fn partial_binomial(&self, n: usize, k: usize) -> Option<ModInt> {
+            let (residues, moduli): (Vec<_>, Vec<_>) = self
+                .parts
+                .iter()
+                .map(|part| (part.binomial(n, k).get(), part.get_mod() as i64))
+                .unzip();
+            let (x, _) = crt(&residues, &moduli)?;
+            Some(ModInt::new(x, self.modulo))
+        }
+    }
+
+    impl BinomialCoefficient for BCTArbitraryMod {}
+
+    #[test]
+    fn lucas_test() {
+        // パスカルの三角形と比較する
+        let n = 60;
+        for &p in &[2, 3, 5, 7, 13] {
+            let tbl = BCTLucas::new(p);
+            let mut row = vec![1usize];
+            for i in 0..=n {
+                for (k, &c) in row.iter().enumerate() {
+                    assert_eq!(tbl.binomial(i, k).get() as usize, c);
+                }
+                assert_eq!(tbl.binomial(i, i + 1).get(), 0);
+                let mut next = vec![1; i + 2];
+                for k in 1..=i {
+                    next[k] = (row[k - 1] + row[k]) % p;
+                }
+                row = next;
+            }
+        }
+
+        // C(10^18, 10^9) mod 7 は素数冪版と一致する
+        let (big_n, big_k) = (1_000_000_000_000_000_000, 1_000_000_000);
+        assert_eq!(
+            BCTLucas::new(7).binomial(big_n, big_k),
+            BCTPrimePower::new(7, 1).binomial(big_n, big_k)
+        );
+        assert_eq!(
+            BCTLucas::new(1_000_003)
+                .binomial(1_000_003 * 5 + 3, 1_000_003 * 2 + 1)
+                .get(),
+            30
+        );
+    }
+
+    #[test]
+    fn arbitrary_mod_test() {
+        let n = 60;
+        for m in 1..=100 {
+            let tbl = BCTArbitraryMod::new(m);
+            let mut row = vec![1 % m];
+            for i in 0..=n {
+                for (k, &c) in row.iter().enumerate() {
+                    assert_eq!(
+                        tbl.binomial(i, k).get() as usize,
+                        c,
+                        "{} C {} mod {}",
+                        i,
+                        k,
+                        m
+                    );
+                }
+                let mut next = vec![1 % m; i + 2];
+                for k in 1..=i {
+                    next[k] = (row[k - 1] + row[k]) % m;
+                }
+                row = next;
+            }
+        }
+
+        for &(p, e) in &[(2, 10), (3, 5), (5, 3)] {
+            let tbl = BCTPrimePower::new(p, e);
+            assert_eq!(tbl.get_mod(), p.pow(e as u32));
+            // C(2^10, 2^9) = C(1024, 512) は 2 でちょうど1回割り切れる
+            if p == 2 {
+                assert_eq!(tbl.binomial(1024, 512).get() % 4, 2);
+            }
+        }
+
+        // C(100, 50) = 100891344545564193334812497256
+        let tbl = BCTArbitraryMod::new(1_000_000);
+        assert_eq!(tbl.binomial(100, 50).get(), 497_256);
+        assert_eq!(tbl.binomial(30, 15).get(), 155_117_520 % 1_000_000);
+    }
 }
@@ -14,7 +14,11 @@ pub mod combination {
         permutation(n, k) / k.factorial()
     }
 
-    pub fn combination_with_table<T: BinomialCoefficient>(table: &T, n: usize, k: usize) -> ModInt {
+    pub fn combination_with_table<T: BinomialCoefficient>(
+        table: &T,
+        n: usize,
+        k: usize,
+    ) -> T::Output {
         table.binomial(n, k)
     }
 }
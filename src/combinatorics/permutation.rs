@@ -3,7 +3,8 @@ pub use permutation::*;
 pub mod permutation {
     use crate::combinatorics::binomial_coefficient::BCTDP;
     use crate::combinatorics::factorial::Factoriable;
-    use crate::modint::ModInt;
+    use num_traits::Zero;
+    use std::ops::Mul;
 
     /// `n P k` を `O(k)` で
     ///
@@ -12,9 +13,13 @@ pub mod permutation {
         n.falling(k)
     }
 
-    pub fn permutation_with_table(table: &BCTDP, n: usize, k: usize) -> ModInt {
+    pub fn permutation_with_table<T: Copy + Zero + Mul<Output = T>>(
+        table: &BCTDP<T>,
+        n: usize,
+        k: usize,
+    ) -> T {
         if k > n {
-            ModInt::new(0, table.get_mod())
+            T::zero()
         } else {
             table.factorial(n) * table.factorial_inverse(n - k)
         }
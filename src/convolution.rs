@@ -0,0 +1,453 @@
+use cargo_snippet::snippet;
+
+use crate::modular::primitive_root;
+use crate::static_modint::{ModIntBase, Modulus, StaticModInt};
+
+/// NTTに使う素数
+///
+/// いずれも`c * 2^k + 1`の形をしていて、長さ`2^k`までの畳み込みができる
+#[snippet("convolution")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Mod754974721 {}
+
+#[snippet("convolution")]
+impl Modulus for Mod754974721 {
+    const VALUE: u32 = 754_974_721;
+    const IS_PRIME: bool = true;
+    const PRIMITIVE_ROOT: u32 = 11;
+}
+
+#[snippet("convolution")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Mod167772161 {}
+
+#[snippet("convolution")]
+impl Modulus for Mod167772161 {
+    const VALUE: u32 = 167_772_161;
+    const IS_PRIME: bool = true;
+    const PRIMITIVE_ROOT: u32 = 3;
+}
+
+#[snippet("convolution")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Mod469762049 {}
+
+#[snippet("convolution")]
+impl Modulus for Mod469762049 {
+    const VALUE: u32 = 469_762_049;
+    const IS_PRIME: bool = true;
+    const PRIMITIVE_ROOT: u32 = 3;
+}
+
+/// バタフライ演算で段ごとに回転因子へ掛ける値の表
+///
+/// ACL(AtCoder Library)と同じく、`sum_e[i]`は
+/// ブロック番号の末尾の`1`が`i`個のときに掛ける値
+#[snippet("convolution")]
+#[derive(Debug, Clone)]
+struct NttInfo {
+    sum_e: Vec<u32>,
+    sum_ie: Vec<u32>,
+}
+
+#[snippet("convolution")]
+impl NttInfo {
+    fn new<M: Modulus>() -> Self {
+        let modulo = M::VALUE;
+        let g = if M::PRIMITIVE_ROOT != 0 {
+            M::PRIMITIVE_ROOT
+        } else {
+            primitive_root(modulo as usize) as u32
+        };
+        let cnt2 = (modulo - 1).trailing_zeros() as usize;
+        let mut e = StaticModInt::<M>::raw(g).pow(((modulo - 1) >> cnt2) as u64);
+        let mut ie = e.inv();
+
+        // es[i]は1の2^(i + 2)乗根
+        let mut es = vec![StaticModInt::<M>::raw(0); cnt2.saturating_sub(1)];
+        let mut ies = es.clone();
+        for i in (2..=cnt2).rev() {
+            es[i - 2] = e;
+            ies[i - 2] = ie;
+            e *= e;
+            ie *= ie;
+        }
+
+        let mut sum_e = Vec::with_capacity(es.len());
+        let mut now = StaticModInt::<M>::raw(1);
+        for (&e, &ie) in es.iter().zip(ies.iter()) {
+            sum_e.push((e * now).val());
+            now *= ie;
+        }
+        let mut sum_ie = Vec::with_capacity(ies.len());
+        let mut now = StaticModInt::<M>::raw(1);
+        for (&e, &ie) in es.iter().zip(ies.iter()) {
+            sum_ie.push((ie * now).val());
+            now *= e;
+        }
+        NttInfo { sum_e, sum_ie }
+    }
+
+    /// modごとに一度だけ表を作り、スレッドごとに使い回す
+    fn get<M: Modulus>() -> Self {
+        thread_local! {
+            static CACHE: std::cell::RefCell<std::collections::HashMap<u32, NttInfo>> =
+                std::cell::RefCell::new(std::collections::HashMap::new());
+        }
+        CACHE.with(|c| {
+            c.borrow_mut()
+                .entry(M::VALUE)
+                .or_insert_with(NttInfo::new::<M>)
+                .clone()
+        })
+    }
+}
+
+/// 結果をビット反転した順で返すNTT
+#[snippet("convolution")]
+fn butterfly<M: Modulus>(a: &mut [StaticModInt<M>], info: &NttInfo) {
+    let log = a.len().trailing_zeros();
+    for ph in 1..=log {
+        let blocks = 1 << (ph - 1);
+        let half = 1 << (log - ph);
+        let mut now = StaticModInt::<M>::raw(1);
+        for block in 0..blocks {
+            let offset = block << (log - ph + 1);
+            let (lo, hi) = a[offset..offset + 2 * half].split_at_mut(half);
+            for (left, right) in lo.iter_mut().zip(hi.iter_mut()) {
+                let (x, y) = (*left, *right * now);
+                *left = x + y;
+                *right = x - y;
+            }
+            now *= StaticModInt::raw(info.sum_e[(!block).trailing_zeros() as usize]);
+        }
+    }
+}
+
+/// ビット反転した順の列を受け取る逆NTT(`1 / n`倍はしない)
+#[snippet("convolution")]
+fn butterfly_inv<M: Modulus>(a: &mut [StaticModInt<M>], info: &NttInfo) {
+    let log = a.len().trailing_zeros();
+    for ph in (1..=log).rev() {
+        let blocks = 1 << (ph - 1);
+        let half = 1 << (log - ph);
+        let mut inow = StaticModInt::<M>::raw(1);
+        for block in 0..blocks {
+            let offset = block << (log - ph + 1);
+            let (lo, hi) = a[offset..offset + 2 * half].split_at_mut(half);
+            for (left, right) in lo.iter_mut().zip(hi.iter_mut()) {
+                let (x, y) = (*left, *right);
+                *left = x + y;
+                *right = (x - y) * inow;
+            }
+            inow *= StaticModInt::raw(info.sum_ie[(!block).trailing_zeros() as usize]);
+        }
+    }
+}
+
+/// ビット反転の並べ替え
+#[snippet("convolution")]
+fn bit_reverse<T>(a: &mut [T]) {
+    let n = a.len();
+    let mut rev = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while rev & bit != 0 {
+            rev ^= bit;
+            bit >>= 1;
+        }
+        rev ^= bit;
+        if i < rev {
+            a.swap(i, rev);
+        }
+    }
+}
+
+/// 数論変換(in-place)
+///
+/// `a.len()`は2冪で、`M::VALUE - 1`を割り切る必要がある
+///
+/// `invert`が`true`なら逆変換(`1 / n`倍まで行う)
+///
+/// 原始根は`M::PRIMITIVE_ROOT`を使い、回転因子の表はmodごとに一度だけ作る
+///
+/// `O(n log n)`
+#[snippet("convolution")]
+pub fn ntt<M: Modulus>(a: &mut [StaticModInt<M>], invert: bool) {
+    let n = a.len();
+    if n <= 1 {
+        return;
+    }
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    assert_eq!(
+        (M::VALUE as usize - 1) % n,
+        0,
+        "length is too long for this modulus"
+    );
+
+    let info = NttInfo::get::<M>();
+    if invert {
+        bit_reverse(a);
+        butterfly_inv(a, &info);
+        let n_inv = StaticModInt::<M>::from(n).inv();
+        for elem in a.iter_mut() {
+            *elem *= n_inv;
+        }
+    } else {
+        butterfly(a, &info);
+        bit_reverse(a);
+    }
+}
+
+/// 愚直な畳み込み
+#[snippet("convolution")]
+fn convolution_naive<T: ModIntBase>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut res = vec![T::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            res[i + j] += x * y;
+        }
+    }
+    res
+}
+
+/// 畳み込み
+///
+/// `c[k] = Σ_{i + j = k} a[i] * b[j]`
+///
+/// modはNTTが使える素数(`998244353`など)である必要がある
+///
+/// `O((n + m) log (n + m))`
+#[snippet("convolution")]
+pub fn convolution<M: Modulus>(
+    a: &[StaticModInt<M>],
+    b: &[StaticModInt<M>],
+) -> Vec<StaticModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if std::cmp::min(a.len(), b.len()) <= 60 {
+        return convolution_naive(a, b);
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+
+    let mut fa = a.to_vec();
+    fa.resize(n, StaticModInt::raw(0));
+    let mut fb = b.to_vec();
+    fb.resize(n, StaticModInt::raw(0));
+    assert_eq!(
+        (M::VALUE as usize - 1) % n,
+        0,
+        "length is too long for this modulus"
+    );
+
+    // ビット反転した順のまま掛け合わせて戻すので並べ替えは要らない
+    let info = NttInfo::get::<M>();
+    butterfly(&mut fa, &info);
+    butterfly(&mut fb, &info);
+    for (x, y) in fa.iter_mut().zip(fb.iter()) {
+        *x *= *y;
+    }
+    butterfly_inv(&mut fa, &info);
+    fa.truncate(len);
+    let n_inv = StaticModInt::<M>::from(n).inv();
+    for x in fa.iter_mut() {
+        *x *= n_inv;
+    }
+    fa
+}
+
+/// 3つのNTT素数それぞれでの畳み込みの結果
+#[snippet("convolution")]
+type ThreePrimes = (
+    Vec<StaticModInt<Mod754974721>>,
+    Vec<StaticModInt<Mod167772161>>,
+    Vec<StaticModInt<Mod469762049>>,
+);
+
+/// 3つのNTT素数それぞれで畳み込む
+#[snippet("convolution")]
+fn convolution_three_primes(a: &[i64], b: &[i64]) -> ThreePrimes {
+    fn conv<M: Modulus>(a: &[i64], b: &[i64]) -> Vec<StaticModInt<M>> {
+        let fa = a.iter().map(|&x| StaticModInt::from(x)).collect::<Vec<_>>();
+        let fb = b.iter().map(|&x| StaticModInt::from(x)).collect::<Vec<_>>();
+        convolution(&fa, &fb)
+    }
+    (conv(a, b), conv(a, b), conv(a, b))
+}
+
+/// 任意modでの畳み込み
+///
+/// 3つのNTT素数で畳み込んだ結果をGarnerのアルゴリズムで復元する
+///
+/// `min(n, m) * modulo^2 < 754974721 * 167772161 * 469762049 (≒ 5.9 * 10^25)`
+/// であれば正しい
+///
+/// `O((n + m) log (n + m))`
+#[snippet("convolution")]
+pub fn convolution_arbitrary_mod<T: ModIntBase>(a: &[T], b: &[T]) -> Vec<T> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let to_i64 = |v: &[T]| v.iter().map(|x| x.val() as i64).collect::<Vec<_>>();
+    let (c1, c2, c3) = convolution_three_primes(&to_i64(a), &to_i64(b));
+
+    let m1 = Mod754974721::VALUE as u64;
+    let m2 = Mod167772161::VALUE as u64;
+    let m1_inv_m2 = StaticModInt::<Mod167772161>::from(m1).inv();
+    let m1m2_inv_m3 = StaticModInt::<Mod469762049>::from(m1 * m2).inv();
+    let m1m2 = T::from_u64(m1) * T::from_u64(m2);
+
+    c1.iter()
+        .zip(c2.iter())
+        .zip(c3.iter())
+        .map(|((&x1, &x2), &x3)| {
+            let v1 = x1.val() as u64;
+            let v2 = ((x2 - StaticModInt::from(v1)) * m1_inv_m2).val() as u64;
+            let v3 = ((x3 - StaticModInt::from(v1) - StaticModInt::from(v2 * m1)) * m1m2_inv_m3)
+                .val() as u64;
+            T::from_u64(v1) + T::from_u64(v2) * T::from_u64(m1) + T::from_u64(v3) * m1m2
+        })
+        .collect()
+}
+
+/// 整数列の畳み込みを`i64`で正確に求める
+///
+/// 結果の各要素が`i64`に収まることを仮定する
+///
+/// `O((n + m) log (n + m))`
+#[snippet("convolution")]
+pub fn convolution_i64(a: &[i64], b: &[i64]) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    const M1: u64 = 754_974_721;
+    const M2: u64 = 167_772_161;
+    const M3: u64 = 469_762_049;
+    const M2M3: u64 = M2 * M3;
+    const M1M3: u64 = M1 * M3;
+    const M1M2: u64 = M1 * M2;
+    let m1m2m3 = M1M2.wrapping_mul(M3);
+
+    // 各素数の上での 1 / (他の2つの積)
+    let i1 = StaticModInt::<Mod754974721>::from(M2M3).inv().val() as u64;
+    let i2 = StaticModInt::<Mod167772161>::from(M1M3).inv().val() as u64;
+    let i3 = StaticModInt::<Mod469762049>::from(M1M2).inv().val() as u64;
+
+    let (c1, c2, c3) = convolution_three_primes(a, b);
+
+    // 負の値に由来するずれを、M1で見た差から補正する
+    let offset = [
+        0,
+        0,
+        m1m2m3,
+        2u64.wrapping_mul(m1m2m3),
+        3u64.wrapping_mul(m1m2m3),
+    ];
+
+    c1.iter()
+        .zip(c2.iter())
+        .zip(c3.iter())
+        .map(|((&x1, &x2), &x3)| {
+            let mut x = 0u64;
+            x = x.wrapping_add((x1.val() as u64 * i1 % M1).wrapping_mul(M2M3));
+            x = x.wrapping_add((x2.val() as u64 * i2 % M2).wrapping_mul(M1M3));
+            x = x.wrapping_add((x3.val() as u64 * i3 % M3).wrapping_mul(M1M2));
+            let diff = (x1.val() as i64 - (x % M1) as i64).rem_euclid(M1 as i64);
+            x.wrapping_sub(offset[(diff % 5) as usize]) as i64
+        })
+        .collect()
+}
+
+#[test]
+fn convolution_test() {
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    let a = [1, 2, 3, 4]
+        .iter()
+        .map(|&x| Mint::new(x))
+        .collect::<Vec<_>>();
+    let b = [5, 6, 7, 8, 9]
+        .iter()
+        .map(|&x| Mint::new(x))
+        .collect::<Vec<_>>();
+    let c = convolution(&a, &b)
+        .iter()
+        .map(|x| x.val())
+        .collect::<Vec<_>>();
+    assert_eq!(c, vec![5, 16, 34, 60, 70, 70, 59, 36]);
+    assert!(convolution::<crate::static_modint::Mod998244353>(&[], &b).is_empty());
+
+    // NTTを通る長さで愚直と比較する
+    let a = (0..300).map(|i| Mint::new(i * i + 7)).collect::<Vec<_>>();
+    let b = (0..200).map(|i| Mint::new(-i * 31 + 5)).collect::<Vec<_>>();
+    assert_eq!(convolution(&a, &b), convolution_naive(&a, &b));
+}
+
+#[test]
+fn ntt_test() {
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    let n = 16;
+    let a = (0..n).map(|i| Mint::new(i * i + 3)).collect::<Vec<_>>();
+    let w = Mint::raw(3).pow((998_244_353 - 1) / n as u64);
+    let naive = (0..n)
+        .map(|k| {
+            a.iter()
+                .enumerate()
+                .fold(Mint::raw(0), |s, (j, &x)| s + x * w.pow((j * k) as u64))
+        })
+        .collect::<Vec<_>>();
+
+    let mut b = a.clone();
+    ntt(&mut b, false);
+    assert_eq!(b, naive);
+    ntt(&mut b, true);
+    assert_eq!(b, a);
+
+    // 原始根を与えないmodでも実行時に求めて使える
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    enum Mod17 {}
+    impl Modulus for Mod17 {
+        const VALUE: u32 = 17;
+        const IS_PRIME: bool = true;
+    }
+    let a = (0..8).map(StaticModInt::<Mod17>::new).collect::<Vec<_>>();
+    let mut b = a.clone();
+    ntt(&mut b, false);
+    ntt(&mut b, true);
+    assert_eq!(b, a);
+}
+
+#[test]
+fn convolution_arbitrary_mod_test() {
+    use crate::static_modint::ModInt1000000007 as Mint;
+
+    let a = (0..200)
+        .map(|i| Mint::new(1_000_000_006 - i * 3))
+        .collect::<Vec<_>>();
+    let b = (0..150)
+        .map(|i| Mint::new(999_999_999 - i * i))
+        .collect::<Vec<_>>();
+    assert_eq!(convolution_arbitrary_mod(&a, &b), convolution_naive(&a, &b));
+}
+
+#[test]
+fn convolution_i64_test() {
+    let a = (0..100)
+        .map(|i| (i * 7919 % 1001 - 500) * 1_000_000)
+        .collect::<Vec<i64>>();
+    let b = (0..80)
+        .map(|i| (i * 104_729 % 2001 - 1000) * 3_000)
+        .collect::<Vec<i64>>();
+    let mut expected = vec![0i64; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            expected[i + j] += x * y;
+        }
+    }
+    assert_eq!(convolution_i64(&a, &b), expected);
+    assert_eq!(convolution_i64(&[-1], &[1]), vec![-1]);
+}
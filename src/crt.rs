@@ -0,0 +1,154 @@
+use cargo_snippet::snippet;
+
+use crate::number_theory::{ext_gcd, inv_mod};
+
+/// 中国剰余定理
+///
+/// `x ≡ r1 (mod m1)`, `x ≡ r2 (mod m2)` を満たす`x`を`x ≡ r (mod lcm(m1, m2))`の形で求める
+///
+/// `m1, m2`は互いに素でなくてもよい
+///
+/// 返り値は`(r, lcm(m1, m2))`で、`0 <= r < lcm(m1, m2)`
+///
+/// 解が存在しなければ`None`
+///
+/// # Panic
+/// `m1 < 1 || m2 < 1`のとき
+#[snippet("crt")]
+pub fn crt_pair(p1: (i64, i64), p2: (i64, i64)) -> Option<(i64, i64)> {
+    let ((r1, m1), (r2, m2)) = (p1, p2);
+    assert!(m1 >= 1 && m2 >= 1, "modulo number must be positive");
+    let (mut r0, mut m0) = (r1.rem_euclid(m1), m1);
+    let (mut r1, mut m1) = (r2.rem_euclid(m2), m2);
+    if m0 < m1 {
+        std::mem::swap(&mut r0, &mut r1);
+        std::mem::swap(&mut m0, &mut m1);
+    }
+    if m0 % m1 == 0 {
+        return if r0 % m1 != r1 { None } else { Some((r0, m0)) };
+    }
+
+    // x = r0 + m0 * k として k を求める
+    // m0 * im ≡ g (mod m1)
+    let (g, im, _) = ext_gcd(m0, m1);
+    let u1 = m1 / g;
+    if (r1 - r0) % g != 0 {
+        return None;
+    }
+    let k = ((r1 - r0) / g % u1 * im).rem_euclid(u1);
+    let lcm = m0 as i128 * u1 as i128;
+    let r = (r0 as i128 + k as i128 * m0 as i128).rem_euclid(lcm);
+    Some((r as i64, lcm as i64))
+}
+
+/// 中国剰余定理
+///
+/// 全ての`i`について`x ≡ r[i] (mod m[i])`を満たす`x`を`x ≡ y (mod z)`の形で求める
+///
+/// 返り値は`(y, z)`で、`z = lcm(m)`, `0 <= y < z`
+///
+/// 解が存在しなければ`None`
+///
+/// 空の入力に対しては`Some((0, 1))`
+///
+/// `z`が`i64`に収まることを仮定する
+///
+/// `O(n log lcm(m))`
+///
+/// # Panic
+/// `r.len() != m.len()`のとき
+#[snippet("crt")]
+pub fn crt(r: &[i64], m: &[i64]) -> Option<(i64, i64)> {
+    assert_eq!(r.len(), m.len());
+    r.iter()
+        .zip(m.iter())
+        .try_fold((0, 1), |acc, (&ri, &mi)| crt_pair(acc, (ri, mi)))
+}
+
+/// Garnerのアルゴリズム
+///
+/// 互いに素な`m`について`x ≡ r[i] (mod m[i])`を満たす最小の非負整数`x`を、
+/// `modulo`で割った余りとして求める
+///
+/// `x`そのものが巨大になる場合でも使える
+///
+/// `O(n^2)`
+///
+/// # Panic
+/// `r.len() != m.len()`のとき、`m`が互いに素でないとき
+#[snippet("crt")]
+pub fn garner(r: &[i64], m: &[i64], modulo: i64) -> i64 {
+    assert_eq!(r.len(), m.len());
+    let n = r.len();
+    // coeffs[i] = m[0] * ... * m[i-1] (mod m[i]), 最後は mod modulo
+    let mut coeffs = vec![1i64; n + 1];
+    let mut constants = vec![0i64; n + 1];
+    let mods = m
+        .iter()
+        .cloned()
+        .chain(std::iter::once(modulo))
+        .collect::<Vec<_>>();
+    for i in 0..n {
+        let mi = mods[i];
+        let inv = inv_mod(coeffs[i], mi).expect("moduli must be pairwise coprime");
+        let t = ((r[i] - constants[i]).rem_euclid(mi) as i128 * inv as i128 % mi as i128) as i64;
+        for j in i + 1..=n {
+            let mj = mods[j] as i128;
+            constants[j] = ((constants[j] as i128 + t as i128 * coeffs[j] as i128) % mj) as i64;
+            coeffs[j] = (coeffs[j] as i128 * mi as i128 % mj) as i64;
+        }
+    }
+    constants[n].rem_euclid(modulo)
+}
+
+#[test]
+fn crt_pair_test() {
+    assert_eq!(crt_pair((2, 3), (3, 5)), Some((8, 15)));
+    assert_eq!(crt_pair((1, 4), (3, 6)), Some((9, 12)));
+    assert_eq!(crt_pair((1, 4), (2, 6)), None);
+    assert_eq!(crt_pair((-1, 7), (0, 1)), Some((6, 7)));
+    assert_eq!(crt_pair((3, 6), (1, 2)), Some((3, 6)));
+    assert_eq!(crt_pair((3, 6), (0, 2)), None);
+}
+
+#[test]
+fn crt_test() {
+    assert_eq!(crt(&[], &[]), Some((0, 1)));
+    assert_eq!(crt(&[2, 3, 2], &[3, 5, 7]), Some((23, 105)));
+    assert_eq!(crt(&[1, 3, 5], &[4, 6, 10]), Some((45, 60)));
+    assert_eq!(crt(&[1, 2, 5], &[4, 6, 10]), None);
+
+    // 全探索と比較する
+    for m1 in 1..12i64 {
+        for m2 in 1..12i64 {
+            for r1 in 0..m1 {
+                for r2 in 0..m2 {
+                    let lcm = m1 * m2 / num_integer::gcd(m1, m2);
+                    let expected = (0..lcm).find(|x| x % m1 == r1 && x % m2 == r2);
+                    assert_eq!(crt(&[r1, r2], &[m1, m2]), expected.map(|x| (x, lcm)));
+                }
+            }
+        }
+    }
+
+    let big = 1_000_000_000i64;
+    assert_eq!(
+        crt(&[1, 2], &[big, big + 1]),
+        Some((big * big + 1, big * (big + 1)))
+    );
+}
+
+#[test]
+fn garner_test() {
+    let m = [998_244_353i64, 1_000_000_007, 1_000_000_009];
+    let x: i128 = 123_456_789_012_345_678_901_234;
+    let r = m
+        .iter()
+        .map(|&mi| (x % mi as i128) as i64)
+        .collect::<Vec<_>>();
+    for &modulo in &[1_000_000_007i64, 998_244_353, 1 << 40] {
+        assert_eq!(garner(&r, &m, modulo), (x % modulo as i128) as i64);
+    }
+    assert_eq!(garner(&[2, 3, 2], &[3, 5, 7], 1_000_000_007), 23);
+    assert_eq!(garner(&[], &[], 7), 0);
+}
@@ -0,0 +1,441 @@
+use cargo_snippet::snippet;
+
+use crate::static_modint::ModIntBase;
+use num_traits::identities::{One, Zero};
+use std::cell::Cell;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::thread::LocalKey;
+
+/// Barrett reduction
+///
+/// `m`と`floor(2^64 / m)`を持っておき、
+/// 割り算を掛け算とシフトに置き換えて`a * b mod m`を求める
+#[snippet("dynamic_modint")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Barrett {
+    m: u32,
+    im: u64,
+}
+
+#[snippet("dynamic_modint")]
+impl Barrett {
+    /// # Panic
+    /// `m == 0`のとき
+    pub fn new(m: u32) -> Self {
+        assert!(m >= 1, "modulo number must be positive");
+        Self {
+            m,
+            im: (std::u64::MAX / m as u64).wrapping_add(1),
+        }
+    }
+
+    pub fn modulus(&self) -> u32 {
+        self.m
+    }
+
+    /// `a * b mod m`, ただし`a, b < m`
+    pub fn mul(&self, lhs: u32, rhs: u32) -> u32 {
+        let prod = lhs as u64 * rhs as u64;
+        let quot = ((prod as u128 * self.im as u128) >> 64) as u64;
+        let sub = quot.wrapping_mul(self.m as u64);
+        // quotは真の商か1大きいので、引きすぎたときだけmを足し戻す
+        let rem = prod.wrapping_sub(sub);
+        if prod < sub {
+            rem.wrapping_add(self.m as u64) as u32
+        } else {
+            rem as u32
+        }
+    }
+}
+
+/// 実行時に決まるmodを区別するための型
+///
+/// modはこの型ごとにスレッドローカルに一つだけ保持される
+#[snippet("dynamic_modint")]
+pub trait DynamicModulusId: 'static + Copy + Eq + Ord + std::hash::Hash + fmt::Debug {
+    fn barrett() -> &'static LocalKey<Cell<Barrett>>;
+}
+
+/// `DynamicModulusId`を実装したマーカー型を定義するマクロ
+///
+/// 一行目に型名
+///
+/// modは`DynamicModInt::<Id>::set_modulus`で設定するまで`998244353`
+///
+/// ```example
+/// dynamic_modulus_def! {
+///     MyId
+/// }
+///
+/// DynamicModInt::<MyId>::set_modulus(m);
+/// ```
+#[snippet("dynamic_modint")]
+#[macro_export]
+macro_rules! dynamic_modulus_def {
+    {
+        $I:ident
+    } => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $I {}
+
+        impl DynamicModulusId for $I {
+            fn barrett() -> &'static std::thread::LocalKey<std::cell::Cell<Barrett>> {
+                thread_local! {
+                    static BARRETT: std::cell::Cell<Barrett> =
+                        std::cell::Cell::new(Barrett::new(998_244_353));
+                }
+                &BARRETT
+            }
+        }
+    };
+}
+
+#[snippet("dynamic_modint")]
+dynamic_modulus_def! {
+    DefaultId
+}
+
+/// 実行時に一度だけmodを設定して使うModInt
+///
+/// `StaticModInt`と同じく値は`u32`一つだけを持ち、
+/// 掛け算はBarrett reductionで行う
+#[snippet("dynamic_modint")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DynamicModInt<I: DynamicModulusId> {
+    val: u32,
+    phantom: PhantomData<fn() -> I>,
+}
+
+#[snippet("dynamic_modint")]
+pub type DynModInt = DynamicModInt<DefaultId>;
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> DynamicModInt<I> {
+    /// 現在のスレッドでのmodを設定する
+    ///
+    /// 設定より前に作った値は意味を失うので注意
+    ///
+    /// # Panic
+    /// `m == 0`のとき
+    pub fn set_modulus(m: u32) {
+        I::barrett().with(|b| b.set(Barrett::new(m)));
+    }
+
+    /// 現在のmod
+    pub fn modulus() -> u32 {
+        I::barrett().with(|b| b.get().modulus())
+    }
+
+    /// 整数からmodを取って作る
+    ///
+    /// 符号付き・符号なしの全ての整数型を受け付ける
+    pub fn new<T: Into<Self>>(n: T) -> Self {
+        n.into()
+    }
+
+    /// `0 <= v < modulus()`であることを仮定して、そのままの値で作る
+    pub fn raw(v: u32) -> Self {
+        Self {
+            val: v,
+            phantom: PhantomData,
+        }
+    }
+
+    /// 中身の値
+    pub fn val(self) -> u32 {
+        self.val
+    }
+
+    /// 二分累乗法
+    pub fn pow(self, exp: u64) -> Self {
+        <Self as ModIntBase>::pow(self, exp)
+    }
+
+    /// 乗法逆元
+    ///
+    /// # Panic
+    /// 逆元が存在しないとき
+    pub fn inv(self) -> Self {
+        <Self as ModIntBase>::inv(self)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> ModIntBase for DynamicModInt<I> {
+    fn modulus() -> u32 {
+        Self::modulus()
+    }
+
+    fn raw(v: u32) -> Self {
+        Self::raw(v)
+    }
+
+    fn val(self) -> u32 {
+        self.val
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Default for DynamicModInt<I> {
+    fn default() -> Self {
+        Self::raw(0)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> fmt::Display for DynamicModInt<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Add for DynamicModInt<I> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        let v = self.val as u64 + rhs.val as u64;
+        let m = Self::modulus() as u64;
+        Self::raw(if v >= m { v - m } else { v } as u32)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Sub for DynamicModInt<I> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        if self.val >= rhs.val {
+            Self::raw(self.val - rhs.val)
+        } else {
+            Self::raw(Self::modulus() - rhs.val + self.val)
+        }
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Mul for DynamicModInt<I> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw(I::barrett().with(|b| b.get().mul(self.val, rhs.val)))
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Div for DynamicModInt<I> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Neg for DynamicModInt<I> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+/// 代入演算子と、参照との間の演算を実装する
+#[snippet("dynamic_modint")]
+macro_rules! impl_dynamic_modint_ops {
+    ($(($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident)),*) => {
+        $(
+            impl<I: DynamicModulusId> $OpAssign for DynamicModInt<I> {
+                fn $op_assign(&mut self, rhs: Self) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+            impl<'a, I: DynamicModulusId> $OpAssign<&'a Self> for DynamicModInt<I> {
+                fn $op_assign(&mut self, rhs: &'a Self) {
+                    *self = $Op::$op(*self, *rhs);
+                }
+            }
+            impl<'a, I: DynamicModulusId> $Op<&'a DynamicModInt<I>> for DynamicModInt<I> {
+                type Output = Self;
+                fn $op(self, rhs: &'a Self) -> Self {
+                    $Op::$op(self, *rhs)
+                }
+            }
+            impl<'a, I: DynamicModulusId> $Op<DynamicModInt<I>> for &'a DynamicModInt<I> {
+                type Output = DynamicModInt<I>;
+                fn $op(self, rhs: DynamicModInt<I>) -> DynamicModInt<I> {
+                    $Op::$op(*self, rhs)
+                }
+            }
+            impl<'a, 'b, I: DynamicModulusId> $Op<&'b DynamicModInt<I>> for &'a DynamicModInt<I> {
+                type Output = DynamicModInt<I>;
+                fn $op(self, rhs: &'b DynamicModInt<I>) -> DynamicModInt<I> {
+                    $Op::$op(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("dynamic_modint")]
+impl_dynamic_modint_ops!(
+    (Add, add, AddAssign, add_assign),
+    (Sub, sub, SubAssign, sub_assign),
+    (Mul, mul, MulAssign, mul_assign),
+    (Div, div, DivAssign, div_assign)
+);
+
+#[snippet("dynamic_modint")]
+macro_rules! impl_dynamic_modint_from {
+    ($($t:ty),*) => {
+        $(
+            impl<I: DynamicModulusId> From<$t> for DynamicModInt<I> {
+                fn from(n: $t) -> Self {
+                    Self::from_u64(n as u64)
+                }
+            }
+        )*
+    };
+    (signed $($t:ty),*) => {
+        $(
+            impl<I: DynamicModulusId> From<$t> for DynamicModInt<I> {
+                fn from(n: $t) -> Self {
+                    Self::from_i64(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("dynamic_modint")]
+impl_dynamic_modint_from!(usize, u8, u16, u32, u64);
+#[snippet("dynamic_modint")]
+impl_dynamic_modint_from!(signed isize, i8, i16, i32, i64);
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Zero for DynamicModInt<I> {
+    fn zero() -> Self {
+        Self::raw(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.val == 0
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> One for DynamicModInt<I> {
+    fn one() -> Self {
+        Self::raw(1 % Self::modulus())
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Sum for DynamicModInt<I> {
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<'a, I: DynamicModulusId> Sum<&'a Self> for DynamicModInt<I> {
+    fn sum<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.fold(Self::zero(), |a, b| a + *b)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<I: DynamicModulusId> Product for DynamicModInt<I> {
+    fn product<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::one(), |a, b| a * b)
+    }
+}
+
+#[snippet("dynamic_modint")]
+impl<'a, I: DynamicModulusId> Product<&'a Self> for DynamicModInt<I> {
+    fn product<It: Iterator<Item = &'a Self>>(iter: It) -> Self {
+        iter.fold(Self::one(), |a, b| a * *b)
+    }
+}
+
+#[test]
+fn barrett_test() {
+    for &m in &[1u32, 2, 3, 7, 998_244_353, 1_000_000_007, std::u32::MAX] {
+        let b = Barrett::new(m);
+        for &(x, y) in &[
+            (0u64, 0u64),
+            (1, 1),
+            (12345, 67890),
+            (m as u64 - 1, m as u64 - 1),
+        ] {
+            let (x, y) = ((x % m as u64) as u32, (y % m as u64) as u32);
+            assert_eq!(b.mul(x, y) as u64, x as u64 * y as u64 % m as u64);
+        }
+    }
+
+    assert_eq!(
+        Barrett::new(4_000_000_007).mul(2_363_973_614, 2_732_184_566),
+        3_299_120_160
+    );
+
+    // 2^31を超えるmodで愚直と比較する
+    let mut seed = 88_172_645_463_325_252u64;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    for &m in &[
+        2_147_483_659u32,
+        4_000_000_007,
+        std::u32::MAX - 1,
+        std::u32::MAX,
+    ] {
+        let b = Barrett::new(m);
+        for _ in 0..10000 {
+            let x = (next() % m as u64) as u32;
+            let y = (next() % m as u64) as u32;
+            assert_eq!(b.mul(x, y) as u64, x as u64 * y as u64 % m as u64);
+        }
+    }
+}
+
+#[test]
+fn dynamic_modint_test() {
+    dynamic_modulus_def! {
+        TestId
+    }
+    dynamic_modulus_def! {
+        OtherId
+    }
+    type Mint = DynamicModInt<TestId>;
+
+    assert_eq!(Mint::modulus(), 998_244_353);
+
+    Mint::set_modulus(13);
+    assert_eq!(Mint::modulus(), 13);
+
+    let a = Mint::new(-1);
+    let b = Mint::from(20u32);
+    assert_eq!(a.val(), 12);
+    assert_eq!(b.val(), 7);
+    assert_eq!((a + b).val(), 6);
+    assert_eq!((a - b).val(), 5);
+    assert_eq!((a * b).val(), 6);
+    assert_eq!((Mint::new(1) / Mint::new(4)).val(), 10);
+    assert_eq!((-b).val(), 6);
+    assert_eq!(Mint::new(27usize).val(), 1);
+    assert_eq!(b.pow(12).val(), 1);
+    let mut c = a;
+    c *= &b;
+    assert_eq!(c.val(), 6);
+    assert_eq!((1..=4).map(Mint::new).product::<Mint>().val(), 11);
+    assert_eq!((1..=4).map(Mint::new).sum::<Mint>().val(), 10);
+
+    Mint::set_modulus(1_000_000_007);
+    assert_eq!((Mint::new(500_000_004) * Mint::new(2)).val(), 1);
+    assert_eq!(std::mem::size_of::<Mint>(), 4);
+
+    // 別のIdのmodには影響しない
+    assert_eq!(DynamicModInt::<OtherId>::modulus(), 998_244_353);
+}
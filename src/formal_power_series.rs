@@ -0,0 +1,525 @@
+use cargo_snippet::snippet;
+
+use crate::convolution::convolution;
+use crate::modint::ModInt;
+use crate::modular::mod_sqrt;
+use crate::static_modint::{Mod998244353, Modulus, StaticModInt};
+use num_traits::identities::{One, Zero};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 形式的冪級数
+///
+/// `self.0[i]`が`x^i`の係数
+///
+/// modはNTTが使える素数である必要がある
+///
+/// `inv`, `log`, `exp`などは引数`n`で`mod x^n`まで(`n`項)求める
+#[snippet("formal_power_series")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormalPowerSeries<M: Modulus>(pub Vec<StaticModInt<M>>);
+
+#[snippet("formal_power_series")]
+pub type Fps998244353 = FormalPowerSeries<Mod998244353>;
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> FormalPowerSeries<M> {
+    pub fn new(coef: Vec<StaticModInt<M>>) -> Self {
+        FormalPowerSeries(coef)
+    }
+
+    /// 長さ`n`で0埋め
+    pub fn zeros(n: usize) -> Self {
+        FormalPowerSeries(vec![StaticModInt::zero(); n])
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `x^i`の係数、範囲外なら0
+    pub fn coef(&self, i: usize) -> StaticModInt<M> {
+        self.0.get(i).cloned().unwrap_or_else(StaticModInt::zero)
+    }
+
+    /// 先頭`n`項、足りなければ0埋めする
+    pub fn pre(&self, n: usize) -> Self {
+        (0..n).map(|i| self.coef(i)).collect()
+    }
+
+    /// 末尾の0を取り除く
+    pub fn shrink(&mut self) {
+        while self.0.last().map_or(false, |c| c.is_zero()) {
+            self.0.pop();
+        }
+    }
+
+    /// 係数の並びを逆にする
+    pub fn rev(&self) -> Self {
+        self.0.iter().rev().cloned().collect()
+    }
+
+    /// 微分
+    pub fn diff(&self) -> Self {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &c)| c * StaticModInt::from(i))
+            .collect()
+    }
+
+    /// 積分(定数項は0)
+    pub fn integral(&self) -> Self {
+        let n = self.len();
+        // 1 / i を線形時間で求める
+        let mut invs = vec![StaticModInt::<M>::one(); n + 1];
+        let m = M::VALUE as usize;
+        for i in 2..=n {
+            invs[i] = -invs[m % i] * StaticModInt::from(m / i);
+        }
+        std::iter::once(StaticModInt::zero())
+            .chain(self.0.iter().enumerate().map(|(i, &c)| c * invs[i + 1]))
+            .collect()
+    }
+
+    /// `x`での値
+    ///
+    /// `O(n)`
+    pub fn eval(&self, x: StaticModInt<M>) -> StaticModInt<M> {
+        self.0
+            .iter()
+            .rev()
+            .fold(StaticModInt::zero(), |acc, &c| acc * x + c)
+    }
+
+    /// `1 / f mod x^n`
+    ///
+    /// `O(n log n)`
+    ///
+    /// # Panic
+    /// 定数項が0のとき
+    pub fn inv(&self, n: usize) -> Self {
+        assert!(!self.coef(0).is_zero(), "constant term must be non-zero");
+        let mut g = FormalPowerSeries(vec![self.coef(0).inv()]);
+        let mut k = 1;
+        while k < n {
+            k <<= 1;
+            // g <- g * (2 - f * g)
+            let mut t = -(&self.pre(k) * &g).pre(k);
+            t[0] += StaticModInt::from(2u32);
+            g = (&g * &t).pre(k);
+        }
+        g.pre(n)
+    }
+
+    /// `log f mod x^n`
+    ///
+    /// `O(n log n)`
+    ///
+    /// # Panic
+    /// 定数項が1でないとき
+    pub fn log(&self, n: usize) -> Self {
+        assert!(self.coef(0).is_one(), "constant term must be one");
+        if n == 0 {
+            return FormalPowerSeries(Vec::new());
+        }
+        (&self.diff() * &self.inv(n)).pre(n - 1).integral()
+    }
+
+    /// `exp f mod x^n`
+    ///
+    /// `O(n log n)`
+    ///
+    /// # Panic
+    /// 定数項が0でないとき
+    pub fn exp(&self, n: usize) -> Self {
+        assert!(self.coef(0).is_zero(), "constant term must be zero");
+        let mut g = FormalPowerSeries(vec![StaticModInt::one()]);
+        let mut k = 1;
+        while k < n {
+            k <<= 1;
+            // g <- g * (1 - log g + f)
+            let mut t = self.pre(k) - g.log(k);
+            t[0] += StaticModInt::one();
+            g = (&g * &t).pre(k);
+        }
+        g.pre(n)
+    }
+
+    /// `f^k mod x^n`
+    ///
+    /// 定数項が0でもよい
+    ///
+    /// `O(n log n)`
+    pub fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return FormalPowerSeries(vec![StaticModInt::one()]).pre(n);
+        }
+        let lowest = match self.0.iter().position(|c| !c.is_zero()) {
+            Some(i) => i,
+            None => return Self::zeros(n),
+        };
+        if lowest as u128 * k as u128 >= n as u128 {
+            return Self::zeros(n);
+        }
+        let shift = lowest * k as usize;
+        let rest = n - shift;
+
+        let c = self.0[lowest];
+        let c_inv = c.inv();
+        let g = self.0[lowest..]
+            .iter()
+            .map(|&x| x * c_inv)
+            .collect::<Self>()
+            .pre(rest);
+        let g = (g.log(rest) * StaticModInt::from(k)).exp(rest) * c.pow(k);
+
+        std::iter::repeat(StaticModInt::zero())
+            .take(shift)
+            .chain(g.0.into_iter())
+            .collect()
+    }
+
+    /// `g^2 = f mod x^n`となる`g`を一つ求める
+    ///
+    /// 存在しなければ`None`
+    ///
+    /// `O(n log n)`
+    pub fn sqrt(&self, n: usize) -> Option<Self> {
+        let lowest = match self.0.iter().position(|c| !c.is_zero()) {
+            Some(i) => i,
+            None => return Some(Self::zeros(n)),
+        };
+        if lowest % 2 == 1 {
+            return None;
+        }
+        let shift = lowest / 2;
+        if shift >= n {
+            return Some(Self::zeros(n));
+        }
+        let rest = n - shift;
+
+        let lead = self.0[lowest];
+        let root = mod_sqrt(ModInt::new(lead.val(), M::VALUE))?;
+        let lead_inv = lead.inv();
+        let f = self.0[lowest..]
+            .iter()
+            .map(|&x| x * lead_inv)
+            .collect::<Self>();
+
+        let half = StaticModInt::<M>::from(2u32).inv();
+        let mut g = FormalPowerSeries(vec![StaticModInt::one()]);
+        let mut k = 1;
+        while k < rest {
+            k <<= 1;
+            // g <- (g + f / g) / 2
+            g = (&g + &(&f.pre(k) * &g.inv(k)).pre(k)) * half;
+        }
+        let g = g.pre(rest) * StaticModInt::new(root.get());
+
+        Some(
+            std::iter::repeat(StaticModInt::zero())
+                .take(shift)
+                .chain(g.0.into_iter())
+                .collect(),
+        )
+    }
+
+    /// 多項式としての除算、`(商, 余り)`
+    ///
+    /// `O(n log n)`
+    ///
+    /// # Panic
+    /// `rhs`が0のとき
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let mut f = self.clone();
+        f.shrink();
+        let mut g = rhs.clone();
+        g.shrink();
+        assert!(!g.is_empty(), "division by zero polynomial");
+        if f.len() < g.len() {
+            return (FormalPowerSeries(Vec::new()), f);
+        }
+        let len = f.len() - g.len() + 1;
+        let quot = (&f.rev().pre(len) * &g.rev().inv(len)).pre(len).rev();
+        let mut rem = (&f - &(&g * &quot)).pre(g.len() - 1);
+        rem.shrink();
+        (quot, rem)
+    }
+
+    /// 葉が`x - xs[i]`である部分積の木
+    ///
+    /// `tree[1]`が全体の積、`tree[size + i]`が葉
+    fn subproduct_tree(xs: &[StaticModInt<M>]) -> (usize, Vec<Self>) {
+        let size = xs.len().next_power_of_two();
+        let mut tree = vec![FormalPowerSeries(vec![StaticModInt::one()]); 2 * size];
+        for (i, &x) in xs.iter().enumerate() {
+            tree[size + i] = FormalPowerSeries(vec![-x, StaticModInt::one()]);
+        }
+        for i in (1..size).rev() {
+            tree[i] = &tree[2 * i] * &tree[2 * i + 1];
+        }
+        (size, tree)
+    }
+
+    /// 複数の点での値を一度に求める
+    ///
+    /// `O(n log^2 n)`
+    pub fn multipoint_eval(&self, xs: &[StaticModInt<M>]) -> Vec<StaticModInt<M>> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        let (size, tree) = Self::subproduct_tree(xs);
+        let mut rem = vec![FormalPowerSeries(Vec::new()); 2 * size];
+        rem[1] = self.div_rem(&tree[1]).1;
+        for i in 2..size + xs.len() {
+            rem[i] = rem[i / 2].div_rem(&tree[i]).1;
+        }
+        (0..xs.len()).map(|i| rem[size + i].coef(0)).collect()
+    }
+
+    /// `f(xs[i]) = ys[i]`となる次数`n - 1`以下の多項式`f`を求める(Lagrange補間)
+    ///
+    /// `xs`は相異なる必要がある
+    ///
+    /// `O(n log^2 n)`
+    pub fn interpolate(xs: &[StaticModInt<M>], ys: &[StaticModInt<M>]) -> Self {
+        assert_eq!(xs.len(), ys.len());
+        if xs.is_empty() {
+            return FormalPowerSeries(Vec::new());
+        }
+        let (size, tree) = Self::subproduct_tree(xs);
+        let denom = tree[1].diff().multipoint_eval(xs);
+
+        let mut node = vec![FormalPowerSeries(Vec::new()); 2 * size];
+        for i in 0..xs.len() {
+            node[size + i] = FormalPowerSeries(vec![ys[i] / denom[i]]);
+        }
+        for i in (1..size).rev() {
+            node[i] = &(&node[2 * i] * &tree[2 * i + 1]) + &(&node[2 * i + 1] * &tree[2 * i]);
+        }
+        node.swap_remove(1).pre(xs.len())
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> std::iter::FromIterator<StaticModInt<M>> for FormalPowerSeries<M> {
+    fn from_iter<I: IntoIterator<Item = StaticModInt<M>>>(iter: I) -> Self {
+        FormalPowerSeries(iter.into_iter().collect())
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> Index<usize> for FormalPowerSeries<M> {
+    type Output = StaticModInt<M>;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> IndexMut<usize> for FormalPowerSeries<M> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<'a, 'b, M: Modulus> Add<&'b FormalPowerSeries<M>> for &'a FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+    fn add(self, rhs: &'b FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        let n = std::cmp::max(self.len(), rhs.len());
+        (0..n).map(|i| self.coef(i) + rhs.coef(i)).collect()
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<'a, 'b, M: Modulus> Sub<&'b FormalPowerSeries<M>> for &'a FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+    fn sub(self, rhs: &'b FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        let n = std::cmp::max(self.len(), rhs.len());
+        (0..n).map(|i| self.coef(i) - rhs.coef(i)).collect()
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<'a, 'b, M: Modulus> Mul<&'b FormalPowerSeries<M>> for &'a FormalPowerSeries<M> {
+    type Output = FormalPowerSeries<M>;
+    fn mul(self, rhs: &'b FormalPowerSeries<M>) -> FormalPowerSeries<M> {
+        FormalPowerSeries(convolution(&self.0, &rhs.0))
+    }
+}
+
+/// 値同士の演算と代入演算子を参照同士の演算から実装する
+#[snippet("formal_power_series")]
+macro_rules! impl_fps_ops {
+    ($(($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident)),*) => {
+        $(
+            impl<M: Modulus> $Op for FormalPowerSeries<M> {
+                type Output = Self;
+                fn $op(self, rhs: Self) -> Self {
+                    $Op::$op(&self, &rhs)
+                }
+            }
+            impl<M: Modulus> $OpAssign for FormalPowerSeries<M> {
+                fn $op_assign(&mut self, rhs: Self) {
+                    *self = $Op::$op(&*self, &rhs);
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("formal_power_series")]
+impl_fps_ops!(
+    (Add, add, AddAssign, add_assign),
+    (Sub, sub, SubAssign, sub_assign),
+    (Mul, mul, MulAssign, mul_assign)
+);
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> Mul<StaticModInt<M>> for FormalPowerSeries<M> {
+    type Output = Self;
+    fn mul(self, rhs: StaticModInt<M>) -> Self {
+        self.0.into_iter().map(|c| c * rhs).collect()
+    }
+}
+
+#[snippet("formal_power_series")]
+impl<M: Modulus> Neg for FormalPowerSeries<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.0.into_iter().map(|c| -c).collect()
+    }
+}
+
+#[cfg(test)]
+fn fps(v: &[i64]) -> Fps998244353 {
+    v.iter().map(|&x| StaticModInt::new(x)).collect()
+}
+
+#[test]
+fn fps_arith_test() {
+    let f = fps(&[1, 2, 3]);
+    let g = fps(&[4, 5]);
+    assert_eq!(&f + &g, fps(&[5, 7, 3]));
+    assert_eq!(&f - &g, fps(&[-3, -3, 3]));
+    assert_eq!(f.clone() * g.clone(), fps(&[4, 13, 22, 15]));
+    assert_eq!(f.diff(), fps(&[2, 6]));
+    assert_eq!(f.diff().integral(), fps(&[0, 2, 3]));
+    assert_eq!(f.eval(StaticModInt::new(2)).val(), 17);
+
+    let (q, r) = fps(&[4, 13, 22, 16]).div_rem(&g);
+    assert_eq!(q.len(), 3);
+    assert_eq!(q * g.clone() + r.clone(), fps(&[4, 13, 22, 16]));
+    assert!(r.len() < g.len());
+}
+
+#[test]
+fn fps_inv_log_exp_test() {
+    let f = fps(&[5, 4, 3, 2, 1]);
+    let n = 10;
+    assert_eq!((&f * &f.inv(n)).pre(n), fps(&[1]).pre(n));
+
+    // exp(log f) = f
+    let f = fps(&[1, 3, 1, 4, 1, 5, 9, 2]);
+    assert_eq!(f.log(n).exp(n), f.pre(n));
+
+    // exp(x) = Σ x^i / i!
+    let e = fps(&[0, 1]).exp(6);
+    let fact = [1, 1, 2, 6, 24, 120];
+    for i in 0..6 {
+        assert_eq!(e[i] * StaticModInt::new(fact[i]), StaticModInt::one());
+    }
+}
+
+#[test]
+fn fps_pow_sqrt_test() {
+    let f = fps(&[0, 0, 2, 1]);
+    let n = 12;
+    let mut expected = fps(&[1]);
+    for _ in 0..4 {
+        expected = (&expected * &f).pre(n);
+    }
+    assert_eq!(f.pow(4, n), expected);
+    assert_eq!(f.pow(0, 3), fps(&[1, 0, 0]));
+    assert_eq!(f.pow(7, n), Fps998244353::zeros(n));
+
+    let g = fps(&[0, 0, 9, 3, 7, 1]);
+    let sq = g.sqrt(8).unwrap();
+    assert_eq!((&sq * &sq).pre(8), g.pre(8));
+    assert!(fps(&[0, 2]).sqrt(4).is_none());
+    // 3 は mod 998244353 で平方非剰余
+    assert!(fps(&[3, 1]).sqrt(4).is_none());
+}
+
+#[test]
+fn fps_multipoint_interpolate_test() {
+    let f = fps(&[3, 1, 4, 1, 5, 9, 2, 6]);
+    let xs = (0..11)
+        .map(|i| StaticModInt::new(i * i - 7))
+        .collect::<Vec<_>>();
+    let ys = f.multipoint_eval(&xs);
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        assert_eq!(f.eval(x), y);
+    }
+
+    let xs = xs[..8].to_vec();
+    let ys = ys[..8].to_vec();
+    assert_eq!(Fps998244353::interpolate(&xs, &ys), f);
+}
+
+#[test]
+fn fps_counting_test() {
+    use crate::combinatorics::binomial_coefficient::BCTDP;
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    let n = 30;
+    let tbl = BCTDP::<Mint>::with_modint(n);
+
+    // 分割数: Π 1 / (1 - x^k)
+    let mut euler = Fps998244353::zeros(n);
+    euler[0] = Mint::one();
+    for k in 1..n {
+        let mut f = fps(&[1]).pre(n);
+        f[k] = -Mint::one();
+        euler = (&euler * &f).pre(n);
+    }
+    let partition = euler.inv(n);
+    assert_eq!(partition[10].val(), 42);
+    assert_eq!(partition[29].val(), 4565);
+
+    // Bell数: n! [x^n] exp(e^x - 1)
+    let ex = (1..n).map(|i| tbl.factorial_inverse(i)).collect::<Vec<_>>();
+    let bell = std::iter::once(Mint::zero())
+        .chain(ex.into_iter())
+        .collect::<Fps998244353>()
+        .exp(n);
+    let bell = (0..n)
+        .map(|i| (bell[i] * tbl.factorial(i)).val())
+        .collect::<Vec<_>>();
+    assert_eq!(&bell[..8], &[1, 1, 2, 5, 15, 52, 203, 877]);
+
+    // 第二種Stirling数 S(k, i) = Σ_j (-1)^(i - j) j^k / (j! (i - j)!)
+    let k = 10u64;
+    let a = (0..=k as usize)
+        .map(|j| Mint::new(j as i64).pow(k) * tbl.factorial_inverse(j))
+        .collect::<Fps998244353>();
+    let b = (0..=k as usize)
+        .map(|j| {
+            let s = tbl.factorial_inverse(j);
+            if j % 2 == 0 {
+                s
+            } else {
+                -s
+            }
+        })
+        .collect::<Fps998244353>();
+    let stirling = (&a * &b).pre(k as usize + 1);
+    assert_eq!(stirling[3].val(), 9330);
+    assert_eq!(stirling[10].val(), 1);
+}
@@ -0,0 +1,361 @@
+use cargo_snippet::snippet;
+
+/// GF(2)上の行列
+///
+/// 各行を`u64`の配列に詰めて持ち、行同士のxorを64bitずつまとめて行う
+#[snippet("gf2_matrix")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Matrix {
+    height: usize,
+    width: usize,
+    rows: Vec<Vec<u64>>,
+}
+
+#[snippet("gf2_matrix")]
+impl Gf2Matrix {
+    /// サイズ`h x w`を指定して、0で初期化
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            height: h,
+            width: w,
+            rows: vec![vec![0; (w + 63) / 64]; h],
+        }
+    }
+
+    /// 二次元配列から構築
+    ///
+    /// # Panic
+    /// 各行の長さが揃っていないとき
+    pub fn from_bools(a: &[Vec<bool>]) -> Self {
+        let w = a.first().map_or(0, |r| r.len());
+        assert!(
+            a.iter().all(|r| r.len() == w),
+            "rows must have the same length"
+        );
+        let mut res = Self::new(a.len(), w);
+        for (i, r) in a.iter().enumerate() {
+            for (j, &b) in r.iter().enumerate() {
+                res.set(i, j, b);
+            }
+        }
+        res
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// `i`行`j`列目
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        assert!(j < self.width);
+        self.rows[i][j / 64] >> (j % 64) & 1 == 1
+    }
+
+    /// `i`行`j`列目を`b`にする
+    pub fn set(&mut self, i: usize, j: usize, b: bool) {
+        assert!(j < self.width);
+        if b {
+            self.rows[i][j / 64] |= 1 << (j % 64);
+        } else {
+            self.rows[i][j / 64] &= !(1 << (j % 64));
+        }
+    }
+
+    /// 先頭`cols`列について掃き出して既約な階段行列にする
+    ///
+    /// 返り値はピボットの列番号
+    fn gauss_jordan(&mut self, cols: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for col in 0..cols {
+            let rank = pivots.len();
+            let (word, bit) = (col / 64, col % 64);
+            let found = (rank..self.height).find(|&i| self.rows[i][word] >> bit & 1 == 1);
+            let p = match found {
+                Some(p) => p,
+                None => continue,
+            };
+            self.rows.swap(p, rank);
+            let pivot_row = self.rows[rank].clone();
+            for (i, row) in self.rows.iter_mut().enumerate() {
+                if i != rank && row[word] >> bit & 1 == 1 {
+                    for (v, pv) in row[word..].iter_mut().zip(pivot_row[word..].iter()) {
+                        *v ^= *pv;
+                    }
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// 階数
+    ///
+    /// `O(hw min(h, w) / 64)`
+    pub fn rank(&self) -> usize {
+        self.clone().gauss_jordan(self.width).len()
+    }
+
+    /// 連立一次方程式`self * x = b`の解を一つ求める
+    ///
+    /// 解が存在しなければ`None`
+    ///
+    /// `O(hw min(h, w) / 64)`
+    ///
+    /// # Panic
+    /// `b.len() != self.height()`のとき
+    pub fn solve(&self, b: &[bool]) -> Option<Vec<bool>> {
+        assert_eq!(self.height, b.len());
+        let w = self.width;
+        let mut aug = Self::new(self.height, w + 1);
+        for (i, (row, &bi)) in self.rows.iter().zip(b.iter()).enumerate() {
+            aug.rows[i][..row.len()].copy_from_slice(row);
+            aug.set(i, w, bi);
+        }
+        let pivots = aug.gauss_jordan(w);
+        if (pivots.len()..self.height).any(|i| aug.get(i, w)) {
+            return None;
+        }
+        let mut res = vec![false; w];
+        for (i, &c) in pivots.iter().enumerate() {
+            res[c] = aug.get(i, w);
+        }
+        Some(res)
+    }
+
+    /// `self * x = 0`の解空間の基底
+    ///
+    /// 長さは`width - rank`
+    ///
+    /// `O(hw min(h, w) / 64 + w^2)`
+    pub fn kernel(&self) -> Vec<Vec<bool>> {
+        let mut a = self.clone();
+        let pivots = a.gauss_jordan(self.width);
+        let mut is_pivot = vec![false; self.width];
+        for &c in &pivots {
+            is_pivot[c] = true;
+        }
+        (0..self.width)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = vec![false; self.width];
+                v[f] = true;
+                for (i, &c) in pivots.iter().enumerate() {
+                    v[c] = a.get(i, f);
+                }
+                v
+            })
+            .collect()
+    }
+}
+
+/// xorについての線形基底
+///
+/// 挿入した値の部分集合のxorとして表せる値の集合を管理する
+#[snippet("xor_basis")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorBasis {
+    /// `basis[i]`は最上位bitが`i`の基底(なければ0)
+    basis: Vec<u64>,
+    rank: usize,
+}
+
+#[snippet("xor_basis")]
+impl Default for XorBasis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("xor_basis")]
+impl XorBasis {
+    pub fn new() -> Self {
+        Self {
+            basis: vec![0; 64],
+            rank: 0,
+        }
+    }
+
+    /// 基底の大きさ
+    pub fn len(&self) -> usize {
+        self.rank
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rank == 0
+    }
+
+    /// `x`を基底で簡約した値
+    fn reduce(&self, mut x: u64) -> u64 {
+        for i in (0..64).rev() {
+            if x >> i & 1 == 1 && self.basis[i] != 0 {
+                x ^= self.basis[i];
+            }
+        }
+        x
+    }
+
+    /// `x`を追加する
+    ///
+    /// 基底が増えたら`true`、既に表せる値だったら`false`
+    ///
+    /// `O(64)`
+    pub fn insert(&mut self, x: u64) -> bool {
+        let x = self.reduce(x);
+        if x == 0 {
+            return false;
+        }
+        self.basis[63 - x.leading_zeros() as usize] = x;
+        self.rank += 1;
+        true
+    }
+
+    /// `x`が挿入した値のxorで表せるか(0は常に表せる)
+    ///
+    /// `O(64)`
+    pub fn can_represent(&self, x: u64) -> bool {
+        self.reduce(x) == 0
+    }
+
+    /// 表せる値の最大値
+    ///
+    /// `O(64)`
+    pub fn max_xor(&self) -> u64 {
+        self.basis
+            .iter()
+            .rev()
+            .fold(0, |acc, &b| std::cmp::max(acc, acc ^ b))
+    }
+
+    /// 表せる値のうち、小さい方から`k`番目(0-indexed、`0`自身が0番目)
+    ///
+    /// 表せる値は`2^len()`個で、それ以上の`k`には`None`
+    ///
+    /// `O(64^2)`
+    pub fn kth_smallest(&self, k: u64) -> Option<u64> {
+        if self.rank < 64 && k >> self.rank != 0 {
+            return None;
+        }
+        // 各基底の最上位bitが他の基底に現れないように簡約する
+        let mut reduced = self.basis.clone();
+        for i in 0..64 {
+            if reduced[i] == 0 {
+                continue;
+            }
+            for j in i + 1..64 {
+                if reduced[j] >> i & 1 == 1 {
+                    reduced[j] ^= reduced[i];
+                }
+            }
+        }
+        Some(
+            reduced
+                .into_iter()
+                .filter(|&b| b != 0)
+                .enumerate()
+                .filter(|&(t, _)| k >> t & 1 == 1)
+                .fold(0, |acc, (_, b)| acc ^ b),
+        )
+    }
+
+    /// `other`で表せる値も全て表せるようにする
+    ///
+    /// `O(64^2)`
+    pub fn merge(&mut self, other: &Self) {
+        for &b in &other.basis {
+            if b != 0 {
+                self.insert(b);
+            }
+        }
+    }
+}
+
+#[test]
+fn gf2_matrix_test() {
+    let a = Gf2Matrix::from_bools(&[
+        vec![true, true, false, false],
+        vec![false, true, true, false],
+        vec![true, false, true, false],
+    ]);
+    assert_eq!(a.rank(), 2);
+    assert!(a.get(1, 2) && !a.get(2, 1));
+
+    let mul = |x: &[bool]| {
+        (0..a.height())
+            .map(|i| (0..a.width()).fold(false, |acc, j| acc ^ (a.get(i, j) & x[j])))
+            .collect::<Vec<_>>()
+    };
+    let b = vec![true, false, true];
+    let x = a.solve(&b).unwrap();
+    assert_eq!(mul(&x), b);
+    assert!(a.solve(&[true, false, false]).is_none());
+
+    let kernel = a.kernel();
+    assert_eq!(kernel.len(), 2);
+    for v in &kernel {
+        assert!(v.iter().any(|&f| f));
+        assert_eq!(mul(v), vec![false; 3]);
+    }
+
+    // 64bitをまたぐ行列
+    let n = 130;
+    let mut big = Gf2Matrix::new(n, n);
+    for i in 0..n {
+        big.set(i, i, true);
+        big.set(i, (i * 7 + 3) % n, true);
+    }
+    big.set(5, 5, false);
+    big.set(5, 5, true);
+    let rank = big.rank();
+    assert_eq!(rank + big.kernel().len(), n);
+    let target = (0..n).map(|i| i % 3 == 0).collect::<Vec<_>>();
+    if let Some(x) = big.solve(&target) {
+        let res = (0..n)
+            .map(|i| (0..n).fold(false, |acc, j| acc ^ (big.get(i, j) & x[j])))
+            .collect::<Vec<_>>();
+        assert_eq!(res, target);
+    }
+}
+
+#[test]
+fn xor_basis_test() {
+    let values = [0b1011u64, 0b0110, 0b1101, 0b0001, 0b1010];
+    let mut basis = XorBasis::new();
+    let inserted = values.iter().map(|&v| basis.insert(v)).collect::<Vec<_>>();
+    assert_eq!(inserted, vec![true, true, false, true, false]);
+    assert_eq!(basis.len(), 3);
+
+    // 全ての部分集合のxorと比較する
+    let mut all = (0..1 << values.len())
+        .map(|s: usize| {
+            (0..values.len())
+                .filter(|&i| s >> i & 1 == 1)
+                .fold(0, |acc, i| acc ^ values[i])
+        })
+        .collect::<Vec<u64>>();
+    all.sort();
+    all.dedup();
+    assert_eq!(basis.max_xor(), *all.last().unwrap());
+    for (k, &v) in all.iter().enumerate() {
+        assert_eq!(basis.kth_smallest(k as u64), Some(v));
+        assert!(basis.can_represent(v));
+    }
+    assert_eq!(basis.kth_smallest(all.len() as u64), None);
+    assert!(!basis.can_represent(0b10000));
+
+    let mut other = XorBasis::new();
+    other.insert(1 << 63);
+    other.insert(0b1011);
+    basis.merge(&other);
+    assert_eq!(basis.len(), 4);
+    assert_eq!(basis.max_xor(), (1 << 63) | 0b1101);
+
+    let mut full = XorBasis::new();
+    for i in 0..64 {
+        full.insert(1 << i);
+    }
+    assert_eq!(full.kth_smallest(std::u64::MAX), Some(std::u64::MAX));
+}
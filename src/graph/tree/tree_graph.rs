@@ -1,4 +1,7 @@
 use crate::graph::def::Graph;
+use crate::structure::algebraic_traits::idempotent::Idempotent;
+use crate::structure::algebraic_traits::monoid::Monoid;
+use crate::structure::sparse_table::SparseTable;
 
 use cargo_snippet::snippet;
 
@@ -99,6 +102,122 @@ impl<'a> Tree {
     }
 }
 
+/// `(深さ, 頂点)`の組の最小値をとるMonoid
+#[snippet("tree")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MinDepth(usize, usize);
+
+#[snippet("tree")]
+impl Monoid for MinDepth {
+    fn identity() -> Self {
+        MinDepth(std::usize::MAX, std::usize::MAX)
+    }
+
+    fn op(x: &Self, y: &Self) -> Self {
+        if (x.0, x.1) <= (y.0, y.1) {
+            *x
+        } else {
+            *y
+        }
+    }
+}
+
+#[snippet("tree")]
+impl Idempotent for MinDepth {}
+
+/// Euler Tour上の区間最小値として最小共通祖先を求める
+///
+/// 構築 `O(n log n)`
+/// クエリ `O(1)`
+#[snippet("tree")]
+#[derive(Debug, Clone)]
+pub struct EulerTourLCA {
+    /// `in_order[v]` => Euler Tourで`v`に初めて訪れた時刻
+    in_order: Vec<usize>,
+    /// `depth[v]` => 根からの`v`の深さ
+    depth: Vec<usize>,
+    table: SparseTable<MinDepth>,
+}
+
+#[snippet("tree")]
+impl EulerTourLCA {
+    pub fn new(tree: &Tree, root: usize) -> Self {
+        let mut in_order = vec![std::usize::MAX; tree.len()];
+        let mut path = Vec::new();
+        let mut tour_depth = Vec::new();
+        let mut id = 0;
+
+        tree.dfs(
+            root,
+            &mut in_order,
+            &mut path,
+            &mut tour_depth,
+            0,
+            &mut id,
+            std::usize::MAX,
+        );
+
+        let mut depth = vec![0; tree.len()];
+        for (&v, &d) in path.iter().zip(tour_depth.iter()) {
+            depth[v] = d;
+        }
+        let tour = path
+            .into_iter()
+            .zip(tour_depth.into_iter())
+            .map(|(v, d)| MinDepth(d, v))
+            .collect::<Vec<_>>();
+
+        Self {
+            in_order,
+            depth,
+            table: SparseTable::new(&tour),
+        }
+    }
+
+    /// 根からの`v`の深さ
+    pub fn depth(&self, v: usize) -> usize {
+        self.depth[v]
+    }
+
+    /// `u`と`v`の最小共通祖先
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (a, b) = (self.in_order[u], self.in_order[v]);
+        let (from, to) = if a <= b { (a, b + 1) } else { (b, a + 1) };
+        self.table.range(from, to).1
+    }
+
+    /// `u`と`v`の間の辺の本数
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth(u) + self.depth(v) - 2 * self.depth(self.lca(u, v))
+    }
+}
+
+#[snippet("tree")]
+impl Tree {
+    /// `root`を根とした最小共通祖先のテーブルを作る
+    pub fn lca(&self, root: usize) -> EulerTourLCA {
+        EulerTourLCA::new(self, root)
+    }
+}
+
+#[test]
+fn lca_test() {
+    let tree = Tree::new(&[(0, 1), (0, 2), (1, 3), (3, 4), (2, 5), (5, 6), (5, 7)]);
+    let lca = tree.lca(0);
+
+    assert_eq!(lca.lca(4, 3), 3);
+    assert_eq!(lca.lca(4, 6), 0);
+    assert_eq!(lca.lca(6, 7), 5);
+    assert_eq!(lca.lca(7, 2), 2);
+    assert_eq!(lca.lca(1, 1), 1);
+    assert_eq!(lca.dist(4, 7), 6);
+    assert_eq!(lca.depth(6), 3);
+
+    let lca = tree.lca(5);
+    assert_eq!(lca.lca(4, 0), 0);
+    assert_eq!(lca.lca(6, 7), 5);
+}
+
 #[test]
 fn euler_tour_test() {
     let tree = Tree::new(&[(0, 1), (0, 2), (1, 3), (3, 4), (2, 5), (5, 6), (5, 7)]);
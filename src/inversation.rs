@@ -3,7 +3,9 @@ use crate::structure::fenwick_tree::FenwickTree;
 use cargo_snippet::snippet;
 
 #[snippet("inversation")]
-/// 配列の転倒数をFenwickTree<Sum>を利用してカウント
+/// 配列の転倒数をFenwickTreeを利用してカウント
+///
+/// 値は内部で座標圧縮するので、負の値や大きな値を含んでいてもよい
 ///
 /// `O(n log n)`
 ///
@@ -18,13 +20,16 @@ use cargo_snippet::snippet;
 ///     let v = vec![1, 2, 4, 3, 6, 5];
 ///     assert_eq!(inversation(&v), 2);
 /// }
-pub fn inversation(v: &[usize]) -> usize {
-    let m = *v.iter().max().unwrap();
-    let mut f = FenwickTree::new(m + 1);
+pub fn inversation<T: Ord + Copy>(v: &[T]) -> usize {
+    let mut xs = v.to_vec();
+    xs.sort();
+    xs.dedup();
+    let mut f = FenwickTree::<usize>::new(xs.len());
     let mut t = 0usize;
-    for i in 0..v.len() {
-        t += i - f.sum(v[i] + 1);
-        f.add(v[i], 1);
+    for (i, x) in v.iter().enumerate() {
+        let r = xs.binary_search(x).unwrap();
+        t += i - f.accumulate(r + 1);
+        f.merge(r, 1);
     }
     t
 }
@@ -40,3 +45,11 @@ fn inversation_test() {
     let v = vec![1, 2, 4, 3, 6, 5];
     assert_eq!(inversation(&v), 2);
 }
+
+#[test]
+fn inversation_i64_test() {
+    let v = vec![1_000_000_000_000i64, -5, 0, -5];
+    assert_eq!(inversation(&v), 4);
+    let v: Vec<i64> = vec![];
+    assert_eq!(inversation(&v), 0);
+}
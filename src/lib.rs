@@ -2,12 +2,23 @@ pub mod binary_powering;
 pub mod binary_search;
 pub mod bitset;
 pub mod combinatorics;
+pub mod convolution;
+pub mod crt;
+pub mod dynamic_modint;
+pub mod formal_power_series;
+pub mod gf2;
 pub mod graph;
 pub mod inversation;
+pub mod linear_recurrence;
+pub mod matrix;
 pub mod modint;
+pub mod modular;
+pub mod montgomery;
+pub mod number_theory;
 pub mod prime;
 pub mod run_length_encoding;
 pub mod shuffle;
+pub mod static_modint;
 pub mod structure;
 pub mod unique_count;
 
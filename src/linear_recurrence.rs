@@ -0,0 +1,224 @@
+use cargo_snippet::snippet;
+
+use crate::convolution::convolution;
+use crate::static_modint::{Modulus, StaticModInt};
+use num_traits::identities::{One, Zero};
+use std::ops::{Div, Mul, Sub};
+
+/// Berlekamp-Massey
+///
+/// 数列`s`を満たす最短の線形漸化式
+/// `s[i] = c[0] * s[i - 1] + c[1] * s[i - 2] + ... + c[d - 1] * s[i - d]`
+/// の係数`c`を求める
+///
+/// 長さ`d`の漸化式を復元するには`2d`項程度が必要
+///
+/// `O(n^2)`
+#[snippet("linear_recurrence")]
+pub fn berlekamp_massey<T>(s: &[T]) -> Vec<T>
+where
+    T: Copy + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    // c(x) = 1 - c[0] x - c[1] x^2 - ... の形で持つ
+    let mut cur = vec![T::one()];
+    let mut prev = vec![T::one()];
+    let mut prev_disc = T::one();
+    let mut shift = 1;
+    for i in 0..s.len() {
+        let disc = cur
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |acc, (j, &cj)| acc + cj * s[i - j]);
+        if disc.is_zero() {
+            shift += 1;
+            continue;
+        }
+        let coef = disc / prev_disc;
+        let next_len = std::cmp::max(cur.len(), prev.len() + shift);
+        let mut next = cur.clone();
+        next.resize(next_len, T::zero());
+        for (j, &pj) in prev.iter().enumerate() {
+            next[j + shift] = next[j + shift] - coef * pj;
+        }
+        if 2 * (cur.len() - 1) <= i {
+            prev = cur;
+            prev_disc = disc;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        cur = next;
+    }
+    cur.iter().skip(1).map(|&c| T::zero() - c).collect()
+}
+
+/// 愚直な多項式の積
+#[snippet("linear_recurrence")]
+fn poly_mul<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Copy + Zero + Mul<Output = T>,
+{
+    let mut res = vec![T::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            res[i + j] = res[i + j] + x * y;
+        }
+    }
+    res
+}
+
+/// Bostan-Mori
+///
+/// 有理式`p(x) / q(x)`の`x^k`の係数を求める
+///
+/// `q[0]`は逆元を持ち、`p.len() < q.len()`である必要がある
+///
+/// `O(d^2 log k)`、ただし`d = q.len()`
+#[snippet("linear_recurrence")]
+pub fn bostan_mori<T>(p: &[T], q: &[T], mut k: u64) -> T
+where
+    T: Copy + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+    while k > 0 {
+        // q(-x)
+        let q_neg = q
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| if i % 2 == 0 { c } else { T::zero() - c })
+            .collect::<Vec<_>>();
+        let num = poly_mul(&p, &q_neg);
+        let den = poly_mul(&q, &q_neg);
+        p = num.into_iter().skip((k & 1) as usize).step_by(2).collect();
+        q = den.into_iter().step_by(2).collect();
+        k >>= 1;
+    }
+    p.first().cloned().unwrap_or_else(T::zero) / q[0]
+}
+
+/// NTTを使うBostan-Mori
+///
+/// `O(d log d log k)`
+#[snippet("linear_recurrence")]
+pub fn bostan_mori_ntt<M: Modulus>(
+    p: &[StaticModInt<M>],
+    q: &[StaticModInt<M>],
+    mut k: u64,
+) -> StaticModInt<M> {
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+    while k > 0 {
+        let q_neg = q
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| if i % 2 == 0 { c } else { -c })
+            .collect::<Vec<_>>();
+        let num = convolution(&p, &q_neg);
+        let den = convolution(&q, &q_neg);
+        p = num.into_iter().skip((k & 1) as usize).step_by(2).collect();
+        q = den.into_iter().step_by(2).collect();
+        k >>= 1;
+    }
+    p.first().cloned().unwrap_or_else(StaticModInt::zero) / q[0]
+}
+
+/// 線形漸化式`a[i] = c[0] * a[i - 1] + ... + c[d - 1] * a[i - d]`の第`k`項を求める
+///
+/// `a`は初めの`d`項、`c`は`berlekamp_massey`の返り値と同じ形
+///
+/// `O(d^2 log k)`
+#[snippet("linear_recurrence")]
+pub fn kth_term<T>(a: &[T], c: &[T], k: u64) -> T
+where
+    T: Copy + Zero + One + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    let deg = c.len();
+    assert!(a.len() >= deg, "not enough initial terms");
+    if k < a.len() as u64 {
+        return a[k as usize];
+    }
+    if deg == 0 {
+        return T::zero();
+    }
+    // den(x) = 1 - c[0] x - ... - c[d - 1] x^d, num(x) = a(x) den(x) mod x^d
+    let den = std::iter::once(T::one())
+        .chain(c.iter().map(|&ci| T::zero() - ci))
+        .collect::<Vec<_>>();
+    let mut num = poly_mul(&a[..deg], &den);
+    num.truncate(deg);
+    bostan_mori(&num, &den, k)
+}
+
+#[test]
+fn berlekamp_massey_test() {
+    use crate::modint::ModInt;
+
+    let m = 1_000_000_007;
+    // フィボナッチ数列
+    let mut fib = vec![ModInt::new(0, m), ModInt::new(1, m)];
+    for i in 2..20 {
+        let next = fib[i - 1] + fib[i - 2];
+        fib.push(next);
+    }
+    let c = berlekamp_massey(&fib);
+    assert_eq!(c.iter().map(|x| x.get()).collect::<Vec<_>>(), vec![1, 1]);
+
+    // a[i] = 2 a[i - 1] - a[i - 3] + 5 a[i - 4]
+    let mut a = [1, 4, 1, 5]
+        .iter()
+        .map(|&x| ModInt::new(x, m))
+        .collect::<Vec<_>>();
+    for i in 4..30 {
+        let next = a[i - 1] * 2 - a[i - 3] + a[i - 4] * 5;
+        a.push(next);
+    }
+    let c = berlekamp_massey(&a);
+    assert_eq!(
+        c.iter().map(|x| x.get()).collect::<Vec<_>>(),
+        vec![2, 0, m as i64 - 1, 5]
+    );
+
+    assert!(berlekamp_massey(&[ModInt::new(0, m); 5]).is_empty());
+}
+
+#[test]
+fn kth_term_test() {
+    use crate::modint::ModInt;
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    let m = 1_000_000_007;
+    let a = [ModInt::new(0, m), ModInt::new(1, m)];
+    let c = [ModInt::new(1, m), ModInt::new(1, m)];
+    assert_eq!(kth_term(&a, &c, 0).get(), 0);
+    assert_eq!(kth_term(&a, &c, 10).get(), 55);
+    assert_eq!(
+        kth_term(&a, &c, 90).get(),
+        2_880_067_194_370_816_120 % m as i64
+    );
+    // F(10^18) mod 10^9+7
+    assert_eq!(
+        kth_term(&a, &c, 1_000_000_000_000_000_000).get(),
+        209_783_453
+    );
+
+    // 漸化式を復元してから第k項を求める
+    let mut s = vec![Mint::new(3), Mint::new(1), Mint::new(4)];
+    for i in 3..40 {
+        let next = s[i - 1] * Mint::new(7) + s[i - 2] - s[i - 3] * Mint::new(2);
+        s.push(next);
+    }
+    let c = berlekamp_massey(&s);
+    assert_eq!(c.len(), 3);
+    for k in 0..40 {
+        assert_eq!(kth_term(&s[..3], &c, k as u64), s[k]);
+    }
+
+    let q = [Mint::new(1), -Mint::new(1), -Mint::new(1)];
+    let p = [Mint::new(0), Mint::new(1)];
+    assert_eq!(bostan_mori_ntt(&p, &q, 10), Mint::new(55));
+    assert_eq!(
+        bostan_mori_ntt(&p, &q, 1_000_000_000_000_000_000),
+        bostan_mori(&p, &q, 1_000_000_000_000_000_000)
+    );
+}
@@ -0,0 +1,576 @@
+use cargo_snippet::snippet;
+
+use crate::dynamic_modint::{DynamicModInt, DynamicModulusId};
+use crate::modint::ModInt;
+use crate::static_modint::{Modulus, StaticModInt};
+use num_traits::{Bounded, One, Zero};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+
+/// 半環
+///
+/// 加法`add`と乗法`mul`を持ち、それぞれの単位元が`zero`と`one`
+///
+/// 行列の積と累乗に必要な演算
+#[snippet("matrix")]
+pub trait Semiring: Copy + PartialEq {
+    /// 加法の単位元
+    fn zero() -> Self;
+
+    /// 乗法の単位元
+    fn one() -> Self;
+
+    fn add(x: &Self, y: &Self) -> Self;
+
+    fn mul(x: &Self, y: &Self) -> Self;
+}
+
+/// `Zero`、`One`と`Add`、`Mul`を持つ型(プリミティブな数値型や`ModInt`)は
+/// 通常の加法と乗法について半環になる
+#[snippet("matrix")]
+impl<T: Zero + One + Copy + PartialEq + Add<Output = T> + Mul<Output = T>> Semiring for T {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn one() -> Self {
+        One::one()
+    }
+
+    fn add(x: &Self, y: &Self) -> Self {
+        *x + *y
+    }
+
+    fn mul(x: &Self, y: &Self) -> Self {
+        *x * *y
+    }
+}
+
+/// min-plus半環
+///
+/// 加法が`min`、乗法が`+`で、`T::max_value()`を到達不能(無限大)として扱う
+///
+/// 行列の`k`乗で「ちょうど`k`辺を通る最短路」が求まる
+#[snippet("matrix")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinPlus<T>(pub T);
+
+#[snippet("matrix")]
+impl<T: Bounded + Zero + Ord + Copy + Add<Output = T>> Semiring for MinPlus<T> {
+    fn zero() -> Self {
+        MinPlus(T::max_value())
+    }
+
+    fn one() -> Self {
+        MinPlus(T::zero())
+    }
+
+    fn add(x: &Self, y: &Self) -> Self {
+        MinPlus(std::cmp::min(x.0, y.0))
+    }
+
+    fn mul(x: &Self, y: &Self) -> Self {
+        if x.0 == T::max_value() || y.0 == T::max_value() {
+            MinPlus(T::max_value())
+        } else {
+            MinPlus(x.0 + y.0)
+        }
+    }
+}
+
+/// max-plus半環
+///
+/// 加法が`max`、乗法が`+`で、`T::min_value()`を到達不能(負の無限大)として扱う
+#[snippet("matrix")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaxPlus<T>(pub T);
+
+#[snippet("matrix")]
+impl<T: Bounded + Zero + Ord + Copy + Add<Output = T>> Semiring for MaxPlus<T> {
+    fn zero() -> Self {
+        MaxPlus(T::min_value())
+    }
+
+    fn one() -> Self {
+        MaxPlus(T::zero())
+    }
+
+    fn add(x: &Self, y: &Self) -> Self {
+        MaxPlus(std::cmp::max(x.0, y.0))
+    }
+
+    fn mul(x: &Self, y: &Self) -> Self {
+        if x.0 == T::min_value() || y.0 == T::min_value() {
+            MaxPlus(T::min_value())
+        } else {
+            MaxPlus(x.0 + y.0)
+        }
+    }
+}
+
+/// 体
+///
+/// 掃き出し法(行列式、逆行列、階数、連立一次方程式)に必要な演算
+#[snippet("matrix")]
+pub trait Field: Semiring + Sub<Output = Self> + Div<Output = Self> {
+    /// 掃き出しの際に0とみなすかどうか
+    fn is_negligible(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    /// ピボットを選ぶ際の大きさ、最も大きいものを選ぶ
+    ///
+    /// 誤差のない型では0以外なら何でもよいので`1`
+    fn magnitude(&self) -> f64 {
+        if self.is_negligible() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+#[snippet("matrix")]
+impl Field for ModInt {}
+
+#[snippet("matrix")]
+impl<M: Modulus> Field for StaticModInt<M> {}
+
+#[snippet("matrix")]
+impl<I: DynamicModulusId> Field for DynamicModInt<I> {}
+
+/// 誤差を考慮して、絶対値が`1e-9`未満なら0とみなす
+///
+/// ピボットには絶対値が最大のものを選ぶ(部分ピボット選択)
+#[snippet("matrix")]
+impl Field for f64 {
+    fn is_negligible(&self) -> bool {
+        self.abs() < 1e-9
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+/// 半環上の行列
+///
+/// `m[(i, j)]`で`i`行`j`列目の要素にアクセスする
+#[snippet("matrix")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    height: usize,
+    width: usize,
+    data: Vec<Vec<T>>,
+}
+
+#[snippet("matrix")]
+impl<T: Semiring> Matrix<T> {
+    /// サイズ`h x w`を指定して、加法の単位元で初期化
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            height: h,
+            width: w,
+            data: vec![vec![T::zero(); w]; h],
+        }
+    }
+
+    /// `n x n`の単位行列
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::new(n, n);
+        for i in 0..n {
+            res.data[i][i] = T::one();
+        }
+        res
+    }
+
+    /// 二次元配列から構築
+    ///
+    /// # Panic
+    /// 各行の長さが揃っていないとき
+    pub fn from_vec(data: Vec<Vec<T>>) -> Self {
+        let h = data.len();
+        let w = data.first().map_or(0, |r| r.len());
+        assert!(
+            data.iter().all(|r| r.len() == w),
+            "rows must have the same length"
+        );
+        Self {
+            height: h,
+            width: w,
+            data,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// 中身を二次元配列として取り出す
+    pub fn into_vec(self) -> Vec<Vec<T>> {
+        self.data
+    }
+
+    /// 転置
+    pub fn transpose(&self) -> Self {
+        let mut res = Self::new(self.width, self.height);
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                res.data[j][i] = v;
+            }
+        }
+        res
+    }
+
+    /// `k`乗
+    ///
+    /// `O(n^3 log k)`
+    ///
+    /// # Panic
+    /// 正方行列でないとき
+    pub fn pow(&self, mut k: u64) -> Self {
+        assert_eq!(self.height, self.width, "matrix must be square");
+        let mut res = Self::identity(self.height);
+        let mut base = self.clone();
+        while k > 0 {
+            if k & 1 == 1 {
+                res = &res * &base;
+            }
+            base = &base * &base;
+            k >>= 1;
+        }
+        res
+    }
+}
+
+#[snippet("matrix")]
+impl<T: Field> Matrix<T> {
+    /// 先頭`cols`列について掃き出して既約な階段行列にする
+    ///
+    /// 返り値はピボットの列番号と、先頭`cols`列の正方行列としての行列式
+    fn gauss_jordan(&mut self, cols: usize) -> (Vec<usize>, T) {
+        let mut det = T::one();
+        let mut pivots = Vec::new();
+        for col in 0..cols {
+            let rank = pivots.len();
+            let mut p = rank;
+            for i in rank + 1..self.height {
+                if self.data[i][col].magnitude() > self.data[p][col].magnitude() {
+                    p = i;
+                }
+            }
+            if p >= self.height || self.data[p][col].is_negligible() {
+                det = T::zero();
+                continue;
+            }
+            if p != rank {
+                self.data.swap(p, rank);
+                det = T::zero() - det;
+            }
+            let pivot = self.data[rank][col];
+            det = T::mul(&det, &pivot);
+            let inv = T::one() / pivot;
+            for v in self.data[rank][col..].iter_mut() {
+                *v = T::mul(v, &inv);
+            }
+            let pivot_row = self.data[rank].clone();
+            for (i, row) in self.data.iter_mut().enumerate() {
+                if i == rank || row[col].is_negligible() {
+                    continue;
+                }
+                let coef = row[col];
+                for (v, pv) in row[col..].iter_mut().zip(pivot_row[col..].iter()) {
+                    *v = *v - T::mul(&coef, pv);
+                }
+            }
+            pivots.push(col);
+        }
+        (pivots, det)
+    }
+
+    /// 行列式
+    ///
+    /// `O(n^3)`
+    ///
+    /// # Panic
+    /// 正方行列でないとき
+    pub fn det(&self) -> T {
+        assert_eq!(self.height, self.width, "matrix must be square");
+        self.clone().gauss_jordan(self.width).1
+    }
+
+    /// 階数
+    ///
+    /// `O(hw min(h, w))`
+    pub fn rank(&self) -> usize {
+        self.clone().gauss_jordan(self.width).0.len()
+    }
+
+    /// 逆行列
+    ///
+    /// 正則でなければ`None`
+    ///
+    /// `O(n^3)`
+    ///
+    /// # Panic
+    /// 正方行列でないとき
+    pub fn inverse(&self) -> Option<Self> {
+        assert_eq!(self.height, self.width, "matrix must be square");
+        let n = self.height;
+        let mut aug = Self::new(n, 2 * n);
+        for i in 0..n {
+            aug.data[i][..n].copy_from_slice(&self.data[i]);
+            aug.data[i][n + i] = T::one();
+        }
+        if aug.gauss_jordan(n).0.len() < n {
+            return None;
+        }
+        Some(Self::from_vec(
+            aug.data.into_iter().map(|r| r[n..].to_vec()).collect(),
+        ))
+    }
+
+    /// 連立一次方程式`self * x = b`を解く
+    ///
+    /// 返り値は特殊解`x`と、解空間`{x + Σ c_i * basis[i]}`を張る斉次解の基底`basis`
+    ///
+    /// 解が存在しなければ`None`
+    ///
+    /// `O(hw min(h, w))`
+    ///
+    /// # Panic
+    /// `b.len() != self.height()`のとき
+    pub fn solve(&self, b: &[T]) -> Option<(Vec<T>, Vec<Vec<T>>)> {
+        assert_eq!(self.height, b.len());
+        let (h, w) = (self.height, self.width);
+        let mut aug = Self::new(h, w + 1);
+        for ((row, src), &v) in aug.data.iter_mut().zip(self.data.iter()).zip(b.iter()) {
+            row[..w].copy_from_slice(src);
+            row[w] = v;
+        }
+        let (pivots, _) = aug.gauss_jordan(w);
+        if aug.data[pivots.len()..]
+            .iter()
+            .any(|r| !r[w].is_negligible())
+        {
+            return None;
+        }
+
+        let mut sol = vec![T::zero(); w];
+        for (row, &c) in pivots.iter().enumerate() {
+            sol[c] = aug.data[row][w];
+        }
+        let mut is_pivot = vec![false; w];
+        for &c in &pivots {
+            is_pivot[c] = true;
+        }
+        let basis = (0..w)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = vec![T::zero(); w];
+                v[f] = T::one();
+                for (row, &c) in pivots.iter().enumerate() {
+                    v[c] = T::zero() - aug.data[row][f];
+                }
+                v
+            })
+            .collect();
+        Some((sol, basis))
+    }
+}
+
+#[snippet("matrix")]
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.data[i][j]
+    }
+}
+
+#[snippet("matrix")]
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.data[i][j]
+    }
+}
+
+/// # Panic
+/// サイズが一致しないとき
+#[snippet("matrix")]
+impl<'a, T: Semiring> Add for &'a Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: Self) -> Matrix<T> {
+        assert_eq!((self.height, self.width), (rhs.height, rhs.width));
+        let mut res = self.clone();
+        for (row, rrow) in res.data.iter_mut().zip(rhs.data.iter()) {
+            for (v, rv) in row.iter_mut().zip(rrow.iter()) {
+                *v = T::add(v, rv);
+            }
+        }
+        res
+    }
+}
+
+/// `O(hwk)`
+///
+/// # Panic
+/// `self.width() != rhs.height()`のとき
+#[snippet("matrix")]
+impl<'a, T: Semiring> Mul for &'a Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Matrix<T> {
+        assert_eq!(self.width, rhs.height, "matrix size mismatch");
+        let mut res = Matrix::new(self.height, rhs.width);
+        for (row, lrow) in res.data.iter_mut().zip(self.data.iter()) {
+            for (lv, rrow) in lrow.iter().zip(rhs.data.iter()) {
+                for (v, rv) in row.iter_mut().zip(rrow.iter()) {
+                    *v = T::add(v, &T::mul(lv, rv));
+                }
+            }
+        }
+        res
+    }
+}
+
+#[snippet("matrix")]
+impl<T: Semiring> Add for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: Self) -> Matrix<T> {
+        &self + &rhs
+    }
+}
+
+#[snippet("matrix")]
+impl<T: Semiring> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Self) -> Matrix<T> {
+        &self * &rhs
+    }
+}
+
+#[test]
+fn matrix_pow_test() {
+    let m = 1_000_000_007;
+    let fib = Matrix::from_vec(vec![
+        vec![ModInt::new(1, m), ModInt::new(1, m)],
+        vec![ModInt::new(1, m), ModInt::new(0, m)],
+    ]);
+    assert_eq!(fib.pow(0), Matrix::identity(2));
+    assert_eq!(fib.pow(10)[(0, 1)].get(), 55);
+    assert_eq!(
+        fib.pow(1_000_000_000_000_000_000)[(0, 1)].get(),
+        209_783_453
+    );
+
+    let a = Matrix::from_vec(vec![vec![1i64, 2, 3], vec![4, 5, 6]]);
+    let b = a.transpose();
+    assert_eq!((a.clone() * b).into_vec(), vec![vec![14, 32], vec![32, 77]]);
+    assert_eq!((&a + &a)[(1, 2)], 12);
+
+    // ちょうどk辺を通る最短路を、DPと比較する
+    let inf = std::i64::MAX;
+    let edges = [(0, 1, 3), (1, 2, 4), (2, 0, -2), (0, 2, 10), (2, 2, 1)];
+    let mut g = Matrix::new(3, 3);
+    for &(u, v, c) in &edges {
+        g[(u, v)] = MinPlus(c);
+    }
+    let mut dist = vec![0, inf, inf];
+    for k in 1..=8u64 {
+        let mut next = vec![inf; 3];
+        for &(u, v, c) in &edges {
+            if dist[u] != inf {
+                next[v] = std::cmp::min(next[v], dist[u] + c);
+            }
+        }
+        dist = next;
+        let p = g.pow(k);
+        assert_eq!((0..3).map(|v| p[(0, v)].0).collect::<Vec<_>>(), dist);
+    }
+
+    let mut g = Matrix::new(2, 2);
+    g[(0, 1)] = MaxPlus(5i32);
+    g[(1, 0)] = MaxPlus(-1);
+    g[(1, 1)] = MaxPlus(2);
+    assert_eq!(g.pow(3)[(0, 1)], MaxPlus(9));
+    assert_eq!(g.pow(3)[(0, 0)], MaxPlus(6));
+    assert_eq!(g.pow(1)[(0, 0)], MaxPlus(std::i32::MIN));
+}
+
+#[test]
+fn gauss_jordan_test() {
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    let a = Matrix::from_vec(
+        [[2, -1, 0], [-1, 2, -1], [0, -1, 2]]
+            .iter()
+            .map(|r| r.iter().map(|&x| Mint::new(x)).collect())
+            .collect(),
+    );
+    assert_eq!(a.det(), Mint::new(4));
+    assert_eq!(a.rank(), 3);
+    let inv = a.inverse().unwrap();
+    assert_eq!(&a * &inv, Matrix::identity(3));
+
+    let singular = Matrix::from_vec(vec![
+        vec![Mint::new(1), Mint::new(2)],
+        vec![Mint::new(2), Mint::new(4)],
+    ]);
+    assert_eq!(singular.det(), Mint::new(0));
+    assert_eq!(singular.rank(), 1);
+    assert!(singular.inverse().is_none());
+
+    // 行の入れ替えで符号が反転する
+    let swapped = Matrix::from_vec(vec![
+        vec![Mint::new(0), Mint::new(1)],
+        vec![Mint::new(1), Mint::new(0)],
+    ]);
+    assert_eq!(swapped.det(), -Mint::new(1));
+
+    let f = Matrix::from_vec(vec![vec![4.0, 7.0], vec![2.0, 6.0]]);
+    assert!((f.det() - 10.0).abs() < 1e-9);
+    let finv = f.inverse().unwrap();
+    assert!((finv[(0, 0)] - 0.6).abs() < 1e-9);
+    assert!((finv[(1, 0)] + 0.2).abs() < 1e-9);
+
+    // 小さいピボットをそのまま使うと桁落ちで誤差が`1e-9`程度まで膨らむ
+    let eps = 1e-8;
+    let f = Matrix::from_vec(vec![vec![eps, 1.0], vec![1.0, 1.0]]);
+    let (x, _) = f.solve(&[1.0, 2.0]).unwrap();
+    assert!((x[0] - 1.0 / (1.0 - eps)).abs() < 1e-12);
+    assert!((x[1] - (1.0 - 2.0 * eps) / (1.0 - eps)).abs() < 1e-12);
+}
+
+#[test]
+fn solve_test() {
+    let m = 13;
+    let mint = |x: i64| ModInt::new(x, m);
+    // x + 2y + 3z = 6, 2x + 4y + 6z = 12, x + y + z = 3
+    let a = Matrix::from_vec(vec![
+        vec![mint(1), mint(2), mint(3)],
+        vec![mint(2), mint(4), mint(6)],
+        vec![mint(1), mint(1), mint(1)],
+    ]);
+    let b = [mint(6), mint(12), mint(3)];
+    let (x, basis) = a.solve(&b).unwrap();
+    assert_eq!(basis.len(), 1);
+    let check = |v: &[ModInt], rhs: &[ModInt]| {
+        (0..3).all(|i| (0..3).fold(mint(0), |acc, j| acc + a[(i, j)] * v[j]) == rhs[i])
+    };
+    assert!(check(&x, &b));
+    assert!(check(&basis[0], &[mint(0); 3]));
+
+    assert!(a.solve(&[mint(6), mint(11), mint(3)]).is_none());
+
+    let f = Matrix::from_vec(vec![vec![1.0, 1.0], vec![1.0, -1.0]]);
+    let (x, basis) = f.solve(&[3.0, 1.0]).unwrap();
+    assert!(basis.is_empty());
+    assert!((x[0] - 2.0).abs() < 1e-9 && (x[1] - 1.0).abs() < 1e-9);
+}
@@ -1,16 +1,18 @@
 pub use modint::*;
 
 pub mod modint {
-    use num_integer::Integer;
     use num_traits::identities::{One, Zero};
     use num_traits::{Num, Pow};
     use std::cmp::Ordering;
     use std::convert::TryInto;
+    use std::fmt;
+    use std::hash::{Hash, Hasher};
+    use std::iter::{Product, Sum};
     use std::num::NonZeroU32;
-    use std::num::ParseIntError;
     use std::ops::{
-        Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+        Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
     };
+    use std::str::FromStr;
 
     use crate::combinatorics::factorial::Factoriable;
 
@@ -77,49 +79,85 @@ pub mod modint {
 
     impl_into_mint!(usize, u8, u16, u32, u64, isize, i8, i16, i32, i64);
 
+    /// 保持している値`num`同士を比べる
+    ///
+    /// `Dynamic`な値も丸めずに比べるので、`Hash`と整合する
+    ///
+    /// # Panic
+    /// 異なるmod間で比較した時
     impl PartialEq for ModInt {
         fn eq(&self, other: &Self) -> bool {
-            if !check_mod_eq(self, other).1 {
-                panic!("cannot compare these values because they have different modulo number",)
-            }
-            self.get() == other.num
+            self.partial_cmp(other)
+                .expect("cannot compare these values because they have different modulo number")
+                == Ordering::Equal
         }
     }
 
-    // #[snippet("modint")]
-    // impl Eq for ModInt {}
+    impl Eq for ModInt {}
 
     impl PartialOrd for ModInt {
         fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            if !check_mod_eq(self, other).1 {
-                None
-            } else {
-                Some(self.get().cmp(&other.num))
+            match (self._modulo, other._modulo) {
+                (Modulo::Static(p), Modulo::Static(q)) if p != q => None,
+                _ => Some(self.num.cmp(&other.num)),
             }
         }
     }
 
-    // #[snippet("modint")]
-    // impl Ord for ModInt {
-    //     fn cmp(&self, other: &Self) -> Ordering {
-    //         self.partial_cmp(other).unwrap()
-    //     }
-    // }
+    /// # Panic
+    /// 異なるmod間で比較した時
+    impl Ord for ModInt {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.partial_cmp(other)
+                .expect("cannot compare these values because they have different modulo number")
+        }
+    }
+
+    /// `Eq`と整合させるためにmodはハッシュに含めない
+    impl Hash for ModInt {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.num.hash(state);
+        }
+    }
+
+    impl fmt::Display for ModInt {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.num)
+        }
+    }
 
-    fn check_mod_eq(a: &ModInt, b: &ModInt) -> (NonZeroU32, bool) {
+    /// 二つの`ModInt`の演算に使う値と、演算結果が持つべきmod
+    ///
+    /// 片方だけが`Dynamic`なら、その値をもう片方のmodで丸めてから使う
+    ///
+    /// `Dynamic`同士(`zero()`や`one()`同士)の演算は`Dynamic`のまま行う
+    ///
+    /// modが異なるときは`None`
+    fn operands(a: &ModInt, b: &ModInt) -> Option<(i64, i64, Modulo)> {
         match (a._modulo, b._modulo) {
-            (Modulo::Static(a), Modulo::Static(b)) => {
-                if a == b {
-                    (a, true)
-                } else {
-                    // safe becase 1 != 0, yeah
-                    (unsafe { NonZeroU32::new_unchecked(1) }, false)
-                }
-            }
-            (Modulo::Static(m), Modulo::Dynamic) | (Modulo::Dynamic, Modulo::Static(m)) => {
-                (m, true)
+            (Modulo::Static(p), Modulo::Static(q)) if p != q => None,
+            (Modulo::Dynamic, Modulo::Dynamic) => Some((a.num, b.num, Modulo::Dynamic)),
+            (Modulo::Static(m), _) | (_, Modulo::Static(m)) => {
+                let rem = |x: i64| compensated_rem(x, m.get() as usize);
+                Some((rem(a.num), rem(b.num), Modulo::Static(m)))
             }
-            (Modulo::Dynamic, Modulo::Dynamic) => (unsafe { NonZeroU32::new_unchecked(1) }, false),
+        }
+    }
+
+    /// 演算結果の生の値`num`を`modulo`で丸める
+    ///
+    /// # Panic
+    /// `Dynamic`な値が`i64`に収まらないとき
+    fn from_raw(num: i128, modulo: Modulo) -> ModInt {
+        let num = match modulo {
+            Modulo::Static(m) => num.rem_euclid(m.get() as i128) as i64,
+            Modulo::Dynamic => num
+                .try_into()
+                .expect("value without modulo number overflowed i64"),
+        };
+        ModInt {
+            num,
+            _modulo: modulo,
         }
     }
 
@@ -172,28 +210,12 @@ pub mod modint {
         }
 
         /// `a / b == a * b^(-1)` となる `b^(-1)` を求める
+        ///
+        /// # Panic
+        /// 値とmodが互いに素でなく、逆元が存在しないとき
         pub fn inv(&self) -> i64 {
-            // let mut a = self.get();
-            // let m = self.get_mod() as i64;
-            // let mut b = self.get_mod() as i64;
-            // let mut u = 1i64;
-            // let mut v = 0i64;
-
-            // while b != 0 {
-            //     let t = a / b;
-            //     a -= t * b;
-            //     swap(&mut a, &mut b);
-            //     u -= t * v;
-            //     swap(&mut u, &mut v);
-            // }
-
-            // u %= m;
-            // if u < 0 { u += m; }
-            // u
-
-            // impl with num_integar::Integar::extended_gcd ...
-            let x = self.get().extended_gcd(&(self.get_mod() as i64)).x;
-            compensated_rem(x, self.get_mod())
+            crate::number_theory::inv_mod(self.get(), self.get_mod() as i64)
+                .expect("this value has no inverse because it is not coprime to the modulo number")
         }
     }
 
@@ -219,20 +241,9 @@ pub mod modint {
     impl Add<Self> for ModInt {
         type Output = Self;
         fn add(self, rhs: Self) -> Self::Output {
-            let c = check_mod_eq(&self, &rhs);
-            if !c.1 {
-                panic!("modulo between two instance is different!",)
-            }
-
-            let r = self.get() + rhs.num;
-            Self {
-                num: if r >= self.get_mod() as i64 {
-                    r - c.0.get() as i64
-                } else {
-                    r
-                },
-                _modulo: Modulo::Static(c.0),
-            }
+            let (x, y, m) =
+                operands(&self, &rhs).expect("modulo between two instance is different!");
+            from_raw(x as i128 + y as i128, m)
         }
     }
 
@@ -252,18 +263,24 @@ pub mod modint {
         assert_eq!((a + c).get(), 4); // (5 + 7) % 8 == 4
     }
 
+    #[test]
+    fn mint_zero_one() {
+        let z = ModInt::zero() + ModInt::zero();
+        assert!(z.is_zero());
+
+        let a = ModInt::new(3, 5);
+        assert_eq!((z + a).get(), 3);
+        assert_eq!((a - ModInt::one()).get(), 2);
+        assert_eq!((ModInt::zero() - a).get(), 2);
+        assert_eq!((ModInt::one() * a).get_mod(), 5);
+    }
+
     impl Sub<Self> for ModInt {
         type Output = Self;
         fn sub(self, rhs: Self) -> Self::Output {
-            let c = check_mod_eq(&self, &rhs);
-            if !c.1 {
-                panic!("modulo between two instance is different!",)
-            }
-            let num = compensated_rem(self.get() - rhs.get(), c.0.get() as usize);
-            Self {
-                num,
-                _modulo: Modulo::Static(c.0),
-            }
+            let (x, y, m) =
+                operands(&self, &rhs).expect("modulo between two instance is different!");
+            from_raw(x as i128 - y as i128, m)
         }
     }
 
@@ -285,15 +302,9 @@ pub mod modint {
     impl Mul<Self> for ModInt {
         type Output = Self;
         fn mul(self, rhs: Self) -> Self::Output {
-            let c = check_mod_eq(&self, &rhs);
-            if !c.1 {
-                panic!("modulo between two instance is different!",)
-            }
-            let num = compensated_rem(self.get() * rhs.get(), c.0.get() as usize);
-            Self {
-                num,
-                _modulo: Modulo::Static(c.0),
-            }
+            let (x, y, m) =
+                operands(&self, &rhs).expect("modulo between two instance is different!");
+            from_raw(x as i128 * y as i128, m)
         }
     }
 
@@ -305,15 +316,12 @@ pub mod modint {
 
     impl Div<Self> for ModInt {
         type Output = Self;
+        #[allow(clippy::suspicious_arithmetic_impl)]
         fn div(self, rhs: Self) -> Self::Output {
-            let c = check_mod_eq(&self, &rhs);
-            if !c.1 {
-                panic!("modulo between two instance is different!",)
-            }
-            Self {
-                num: self.get() * rhs.inv() % c.0.get() as i64,
-                _modulo: Modulo::Static(c.0),
-            }
+            let (x, y, m) =
+                operands(&self, &rhs).expect("modulo between two instance is different!");
+            let modulo = m.get().expect("cannot divide without modulo number");
+            from_raw(x as i128 * Self::new(y, modulo).inv() as i128, m)
         }
     }
 
@@ -369,13 +377,11 @@ pub mod modint {
     impl Rem for ModInt {
         type Output = Self;
         fn rem(self, rhs: Self) -> Self::Output {
-            let c = check_mod_eq(&self, &rhs);
-            if !c.1 {
-                panic!("modulo between two instance is different!",)
-            }
+            let (x, y, m) =
+                operands(&self, &rhs).expect("modulo between two instance is different!");
             Self {
-                num: self.num % rhs.num,
-                _modulo: Modulo::Static(c.0),
+                num: x % y,
+                _modulo: m,
             }
         }
     }
@@ -386,6 +392,99 @@ pub mod modint {
         }
     }
 
+    impl Neg for ModInt {
+        type Output = Self;
+        fn neg(self) -> Self::Output {
+            from_raw(-(self.num as i128), self._modulo)
+        }
+    }
+
+    /// `&a + &b`のような参照同士や、参照と値の間の演算
+    macro_rules! impl_ref_ops {
+        ($(($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident)),*) => {
+            $(
+                impl<'a> $Op<&'a ModInt> for ModInt {
+                    type Output = ModInt;
+                    fn $op(self, rhs: &'a ModInt) -> ModInt {
+                        $Op::$op(self, *rhs)
+                    }
+                }
+                impl<'a> $Op<ModInt> for &'a ModInt {
+                    type Output = ModInt;
+                    fn $op(self, rhs: ModInt) -> ModInt {
+                        $Op::$op(*self, rhs)
+                    }
+                }
+                impl<'a, 'b> $Op<&'b ModInt> for &'a ModInt {
+                    type Output = ModInt;
+                    fn $op(self, rhs: &'b ModInt) -> ModInt {
+                        $Op::$op(*self, *rhs)
+                    }
+                }
+                impl<'a> $OpAssign<&'a ModInt> for ModInt {
+                    fn $op_assign(&mut self, rhs: &'a ModInt) {
+                        *self = $Op::$op(*self, *rhs);
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_ref_ops!(
+        (Add, add, AddAssign, add_assign),
+        (Sub, sub, SubAssign, sub_assign),
+        (Mul, mul, MulAssign, mul_assign),
+        (Div, div, DivAssign, div_assign),
+        (Rem, rem, RemAssign, rem_assign)
+    );
+
+    impl Sum for ModInt {
+        fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+            iter.fold(Self::zero(), |a, b| a + b)
+        }
+    }
+
+    impl<'a> Sum<&'a ModInt> for ModInt {
+        fn sum<I: Iterator<Item = &'a ModInt>>(iter: I) -> Self {
+            iter.fold(Self::zero(), |a, b| a + b)
+        }
+    }
+
+    impl Product for ModInt {
+        fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+            iter.fold(Self::one(), |a, b| a * b)
+        }
+    }
+
+    impl<'a> Product<&'a ModInt> for ModInt {
+        fn product<I: Iterator<Item = &'a ModInt>>(iter: I) -> Self {
+            iter.fold(Self::one(), |a, b| a * b)
+        }
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn std_traits_test() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let v = (1..=5).map(|x| ModInt::new(x, 7)).collect::<Vec<_>>();
+        assert_eq!(v.iter().sum::<ModInt>().get(), 1);
+        assert_eq!(v.iter().cloned().product::<ModInt>().get(), 1);
+        assert_eq!((-v[1]).get(), 5);
+        assert_eq!((&v[2] + &v[3]).get(), 0);
+        assert_eq!((&v[2] * v[3]).get(), 5);
+        assert_eq!(format!("{}", v[4]), "5");
+
+        let mut hm = HashMap::new();
+        *hm.entry(ModInt::new(8, 7)).or_insert(0) += 1;
+        *hm.entry(ModInt::new(1, 7)).or_insert(0) += 1;
+        assert_eq!(hm[&ModInt::new(1, 7)], 2);
+
+        let bm = v.iter().map(|&x| (x, x.get())).collect::<BTreeMap<_, _>>();
+        assert_eq!(bm.keys().next_back().unwrap().get(), 5);
+        assert_eq!(v.iter().max().unwrap().get(), 5);
+    }
+
     impl Zero for ModInt {
         fn zero() -> Self {
             ModInt {
@@ -410,15 +509,80 @@ pub mod modint {
         }
     }
 
+    /// 文字列から`ModInt`への変換に失敗したときのエラー
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ParseModIntError {
+        /// 数字が一つもない
+        Empty,
+        /// `radix`進数の数字でない文字を含む
+        InvalidDigit,
+        /// modを持たない値が`i64`に収まらない
+        Overflow,
+    }
+
+    impl fmt::Display for ParseModIntError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let msg = match self {
+                ParseModIntError::Empty => "cannot parse integer from empty string",
+                ParseModIntError::InvalidDigit => "invalid digit found in string",
+                ParseModIntError::Overflow => "number too large to fit in i64",
+            };
+            write!(f, "{}", msg)
+        }
+    }
+
+    impl std::error::Error for ParseModIntError {}
+
+    /// 符号と`radix`進数の数字列を読み、`push(acc, d)`で一桁ずつ積み上げる
+    fn parse_digits<F: Fn(i64, i64) -> Option<i64>>(
+        s: &str,
+        radix: u32,
+        push: F,
+    ) -> Result<i64, ParseModIntError> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() {
+            return Err(ParseModIntError::Empty);
+        }
+        let mut num = 0i64;
+        for c in digits.chars() {
+            let d = c.to_digit(radix).ok_or(ParseModIntError::InvalidDigit)?;
+            num = push(num, d as i64).ok_or(ParseModIntError::Overflow)?;
+        }
+        Ok(if negative { -num } else { num })
+    }
+
+    impl ModInt {
+        /// 文字列を一桁ずつ読みながら`m`で割った余りを取る
+        ///
+        /// `i64`に収まらない桁数の数でもよい
+        ///
+        /// 空文字列や`radix`進数の数字でない文字を含むときは`Err`
+        pub fn from_str_radix_with_mod<M: TryInto<u32> + Copy>(
+            s: &str,
+            radix: u32,
+            m: M,
+        ) -> Result<Self, ParseModIntError> {
+            let m = m.try_into().ok().expect("modulo number may be wrong") as i64;
+            let num = parse_digits(s, radix, |acc, d| Some((acc * radix as i64 + d) % m))?;
+            Ok(Self::new(num, m))
+        }
+    }
+
+    /// modを持たない(`Dynamic`な)値になる
+    ///
+    /// 値は`i64`に収まる必要があり、modを持つ値との演算の際にそのmodで丸められる
+    ///
+    /// 桁数の大きな数からmodを取りたいときは`ModInt::from_str_radix_with_mod`を使う
     impl Num for ModInt {
-        type FromStrRadixErr = ParseIntError;
+        type FromStrRadixErr = ParseModIntError;
         fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
-            let num = str
-                .chars()
-                .rev()
-                .enumerate()
-                .map(|(i, b)| radix.pow(i as u32) as i64 * b.to_digit(radix).unwrap() as i64)
-                .sum::<i64>();
+            let num = parse_digits(str, radix, |acc, d| {
+                acc.checked_mul(radix as i64)?.checked_add(d)
+            })?;
             Ok(ModInt {
                 num,
                 _modulo: Modulo::Dynamic,
@@ -426,6 +590,70 @@ pub mod modint {
         }
     }
 
+    impl FromStr for ModInt {
+        type Err = ParseModIntError;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Self::from_str_radix(s, 10)
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn inv_not_coprime_test() {
+        let _ = ModInt::new(4, 6).inv();
+    }
+
+    #[test]
+    fn from_str_test() {
+        let a =
+            ModInt::from_str_radix_with_mod("123456789012345678901234567890", 10, 1_000_000_007)
+                .unwrap();
+        assert_eq!(a.get(), 197_434_842);
+        let b = ModInt::from_str_radix_with_mod("-ff", 16, 7).unwrap();
+        assert_eq!(b.get(), 4);
+        assert!(ModInt::from_str_radix_with_mod("12a", 10, 7).is_err());
+        assert!(ModInt::from_str_radix_with_mod("", 10, 7).is_err());
+        assert!(ModInt::from_str_radix_with_mod("-", 10, 7).is_err());
+
+        assert_eq!(ModInt::from_str_radix("101", 2).unwrap().get(), 5);
+        assert!(ModInt::from_str_radix("12", 2).is_err());
+        let c = "42".parse::<ModInt>().unwrap() + ModInt::new(0, 5);
+        assert_eq!(c.get(), 2);
+        assert!("x".parse::<ModInt>().is_err());
+
+        assert_eq!(
+            ModInt::from_str_radix_with_mod("12a", 10, 7),
+            Err(ParseModIntError::InvalidDigit)
+        );
+        assert_eq!("+".parse::<ModInt>(), Err(ParseModIntError::Empty));
+        assert_eq!(
+            "99999999999999999999".parse::<ModInt>(),
+            Err(ParseModIntError::Overflow)
+        );
+
+        // modを持たない値は、modを持つ値との演算の前に丸められる
+        let big = "10000000000".parse::<ModInt>().unwrap();
+        let d = big * ModInt::new(1_000_000_000, 1_000_000_007);
+        assert_eq!(
+            d.get(),
+            10_000_000_000 % 1_000_000_007 * 1_000_000_000 % 1_000_000_007
+        );
+        assert_eq!((-big + ModInt::new(0, 7)).get(), 3);
+
+        // 比較は丸めずに行うので、ハッシュと整合する
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |x: &ModInt| {
+            let mut h = DefaultHasher::new();
+            x.hash(&mut h);
+            h.finish()
+        };
+        let eight = ModInt::from_str_radix("8", 10).unwrap();
+        assert_ne!(ModInt::new(1, 7), eight);
+        assert_eq!(ModInt::new(8, 11), eight);
+        assert_eq!(hash(&ModInt::new(8, 11)), hash(&eight));
+        assert_ne!(big, ModInt::new(10_000_000_000i64, 1_000_000_007));
+    }
+
     impl Pow<usize> for ModInt {
         type Output = Self;
         // fn pow(self, exp: u32) -> Self::Output {
@@ -0,0 +1,243 @@
+use cargo_snippet::snippet;
+
+use crate::modint::ModInt;
+use crate::prime::trial_division::{factorization, is_prime};
+use num_integer::Integer;
+use num_traits::Pow;
+use std::collections::HashMap;
+
+/// 素数`p`を法として`x^2 = a`となる`x`を一つ求める(Tonelli-Shanks)
+///
+/// 二つある解のうち小さい方を返す
+///
+/// 存在しなければ`None`
+///
+/// `O(log^2 p)`
+///
+/// # Panic
+/// `a`のmodが素数でないとき
+#[snippet("modular")]
+pub fn mod_sqrt(a: ModInt) -> Option<ModInt> {
+    let p = a.get_mod();
+    assert!(is_prime(p), "modulo number must be a prime");
+    if p == 2 || a.get() == 0 {
+        return Some(a);
+    }
+    if a.pow((p - 1) / 2).get() != 1 {
+        return None;
+    }
+
+    // p - 1 = odd * 2^two_exp
+    let mut odd = p - 1;
+    let mut two_exp = 0;
+    while odd % 2 == 0 {
+        odd /= 2;
+        two_exp += 1;
+    }
+
+    // 平方非剰余を探す
+    let non_residue = (2..)
+        .map(|z| ModInt::new(z, p))
+        .find(|z| z.pow((p - 1) / 2).get() == p as i64 - 1)
+        .unwrap();
+
+    let mut level = two_exp;
+    let mut gen = non_residue.pow(odd);
+    let mut rest = a.pow(odd);
+    let mut root = a.pow((odd + 1) / 2);
+    while rest.get() != 1 {
+        let mut i = 0;
+        let mut sq = rest;
+        while sq.get() != 1 {
+            sq *= sq;
+            i += 1;
+        }
+        let fix = gen.pow(1 << (level - i - 1));
+        level = i;
+        gen = fix * fix;
+        rest *= gen;
+        root *= fix;
+    }
+
+    Some(std::cmp::min(root, -root))
+}
+
+/// `x^k = y`となる最小の非負整数`k`を求める(Baby-step Giant-step)
+///
+/// `x`とmodが互いに素でなくてもよい
+///
+/// 存在しなければ`None`
+///
+/// `O(√m)`
+#[snippet("modular")]
+pub fn discrete_log(x: ModInt, y: ModInt) -> Option<usize> {
+    let mut modulo = x.get_mod() as u64;
+    let mut a = x.get() as u64;
+    let mut b = y.get() as u64;
+    if modulo == 1 {
+        return Some(0);
+    }
+
+    // gcd(a, modulo) > 1 の間は両辺をgcdで割って互いに素に帰着する
+    // coef * a^(ans - add) = b (mod modulo)
+    let mut coef = 1;
+    let mut add = 0;
+    loop {
+        let gcd = a.gcd(&modulo);
+        if gcd == 1 {
+            break;
+        }
+        if b == coef {
+            return Some(add);
+        }
+        if b % gcd != 0 {
+            return None;
+        }
+        b /= gcd;
+        modulo /= gcd;
+        add += 1;
+        coef = coef * (a / gcd) % modulo;
+        a %= modulo;
+    }
+    if b % modulo == coef % modulo {
+        return Some(add);
+    }
+
+    let step = (modulo as f64).sqrt().ceil() as u64;
+
+    // baby-step: b * a^j (0 <= j < step)
+    let mut baby = HashMap::with_capacity(step as usize);
+    let mut cur = b % modulo;
+    for j in 0..step {
+        baby.insert(cur, j);
+        cur = cur * a % modulo;
+    }
+
+    // giant-step: coef * a^(step * i) (1 <= i <= step)
+    let giant = (0..step).fold(1 % modulo, |acc, _| acc * a % modulo);
+    let mut cur = coef % modulo;
+    for i in 1..=step {
+        cur = cur * giant % modulo;
+        if let Some(&j) = baby.get(&cur) {
+            return Some((step * i - j) as usize + add);
+        }
+    }
+    None
+}
+
+/// `a^k = 1`となる最小の正整数`k`(位数)を求める
+///
+/// `a`とmodが互いに素でなければ`None`
+///
+/// `O(√m)`
+#[snippet("modular")]
+pub fn multiplicative_order(a: ModInt) -> Option<usize> {
+    let m = a.get_mod();
+    if (a.get() as usize).gcd(&m) != 1 {
+        return None;
+    }
+    if m == 1 {
+        return Some(1);
+    }
+
+    let phi = factorization(m)
+        .iter()
+        .fold(m, |acc, &(p, _)| acc / p * (p - 1));
+
+    let mut ord = phi;
+    for (p, _) in factorization(phi) {
+        while ord % p == 0 && a.pow(ord / p).get() == 1 {
+            ord /= p;
+        }
+    }
+    Some(ord)
+}
+
+/// 素数`p`の最小の原始根を求める
+///
+/// `O(√p)`
+#[snippet("modular")]
+pub fn primitive_root(p: usize) -> usize {
+    if p == 2 {
+        return 1;
+    }
+    let factors = factorization(p - 1);
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&(q, _)| ModInt::new(g, p).pow((p - 1) / q).get() != 1)
+        })
+        .unwrap()
+}
+
+#[test]
+fn mod_sqrt_test() {
+    for &p in &[2usize, 3, 13, 17, 998_244_353, 1_000_000_007] {
+        for a in (0..200).map(|a| a % p) {
+            let a = ModInt::new(a, p);
+            match mod_sqrt(a) {
+                Some(x) => assert_eq!(x * x, a),
+                // オイラーの規準で平方非剰余であることを確かめる
+                None => assert_eq!(a.pow((p - 1) / 2), -ModInt::new(1, p)),
+            }
+        }
+    }
+    assert!(mod_sqrt(ModInt::new(2, 13)).is_none());
+    assert_eq!(mod_sqrt(ModInt::new(10, 13)).unwrap().get(), 6);
+}
+
+#[test]
+#[should_panic]
+fn mod_sqrt_not_prime_test() {
+    let _ = mod_sqrt(ModInt::new(4, 15));
+}
+
+#[test]
+fn discrete_log_test() {
+    for m in 1..40usize {
+        for x in 0..m {
+            for y in 0..m {
+                let mut cur = 1 % m;
+                let mut expected = None;
+                for k in 0..2 * m + 1 {
+                    if cur == y {
+                        expected = Some(k);
+                        break;
+                    }
+                    cur = cur * x % m;
+                }
+                assert_eq!(
+                    discrete_log(ModInt::new(x, m), ModInt::new(y, m)),
+                    expected,
+                    "m = {}, x = {}, y = {}",
+                    m,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    let p = 998_244_353;
+    let x = ModInt::new(3, p);
+    assert_eq!(discrete_log(x, x.pow(123_456_789)), Some(123_456_789));
+}
+
+#[test]
+fn order_test() {
+    assert_eq!(multiplicative_order(ModInt::new(2, 7)), Some(3));
+    assert_eq!(multiplicative_order(ModInt::new(3, 7)), Some(6));
+    assert_eq!(multiplicative_order(ModInt::new(2, 8)), None);
+    assert_eq!(multiplicative_order(ModInt::new(3, 8)), Some(2));
+    assert_eq!(multiplicative_order(ModInt::new(1, 1)), Some(1));
+
+    assert_eq!(primitive_root(2), 1);
+    assert_eq!(primitive_root(7), 3);
+    assert_eq!(primitive_root(998_244_353), 3);
+    assert_eq!(primitive_root(1_000_000_007), 5);
+    assert_eq!(
+        multiplicative_order(ModInt::new(5, 1_000_000_007)),
+        Some(1_000_000_006)
+    );
+}
@@ -0,0 +1,405 @@
+use cargo_snippet::snippet;
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// `a * b mod m`
+///
+/// `u128`を経由するので`m < 2^64`ならオーバーフローしない
+#[snippet("montgomery")]
+pub fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `a^exp mod m`
+///
+/// `number_theory::pow_mod`と同じ
+///
+/// `O(log exp)`
+#[snippet("montgomery")]
+pub fn pow_mod_u64(a: u64, exp: u64, m: u64) -> u64 {
+    crate::number_theory::pow_mod(a, exp, m)
+}
+
+/// 奇数の法`n < 2^64`に対するMontgomery乗算
+///
+/// `R = 2^64`として、`a`を`aR mod n`の形(Montgomery表現)で持つと
+/// 掛け算の剰余が割り算なしで計算できる
+#[snippet("montgomery")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery64 {
+    n: u64,
+    /// `n * n_inv ≡ 1 (mod 2^64)`
+    n_inv: u64,
+    /// `R^2 mod n`
+    r2: u64,
+}
+
+#[snippet("montgomery")]
+impl Montgomery64 {
+    /// # Panic
+    /// `n`が偶数のとき
+    pub fn new(n: u64) -> Self {
+        assert!(
+            n & 1 == 1,
+            "Montgomery multiplication requires an odd modulus"
+        );
+        // Newton法で1回ごとに正しいbit数が倍になる
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r2 = ((std::u128::MAX % n as u128 + 1) % n as u128) as u64;
+        Self { n, n_inv, r2 }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    /// `t / R mod n`, ただし`t < nR`
+    pub fn reduce(&self, t: u128) -> u64 {
+        let (hi, lo) = ((t >> 64) as u64, t as u64);
+        let q = lo.wrapping_mul(self.n_inv);
+        let qn = ((q as u128 * self.n as u128) >> 64) as u64;
+        if hi < qn {
+            hi.wrapping_sub(qn).wrapping_add(self.n)
+        } else {
+            hi - qn
+        }
+    }
+
+    /// 通常の値からMontgomery表現へ
+    pub fn to_mont(&self, a: u64) -> u64 {
+        self.reduce((a % self.n) as u128 * self.r2 as u128)
+    }
+
+    /// Montgomery表現から通常の値へ
+    pub fn from_mont(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    /// Montgomery表現同士の積
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+}
+
+/// `ModInt64`の剰余の取り方
+///
+/// 奇数の法はMontgomery乗算、偶数の法は`u128`での剰余にフォールバックする
+#[snippet("montgomery")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer64 {
+    Montgomery(Montgomery64),
+    Wide(u64),
+}
+
+#[snippet("montgomery")]
+impl Reducer64 {
+    /// # Panic
+    /// `m == 0`のとき
+    pub fn new(m: u64) -> Self {
+        assert!(m >= 1, "modulo number must be positive");
+        if m & 1 == 1 {
+            Reducer64::Montgomery(Montgomery64::new(m))
+        } else {
+            Reducer64::Wide(m)
+        }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        match self {
+            Reducer64::Montgomery(mg) => mg.modulus(),
+            Reducer64::Wide(m) => *m,
+        }
+    }
+
+    fn encode(&self, a: u64) -> u64 {
+        match self {
+            Reducer64::Montgomery(mg) => mg.to_mont(a),
+            Reducer64::Wide(m) => a % m,
+        }
+    }
+
+    fn decode(&self, a: u64) -> u64 {
+        match self {
+            Reducer64::Montgomery(mg) => mg.from_mont(a),
+            Reducer64::Wide(_) => a,
+        }
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        match self {
+            Reducer64::Montgomery(mg) => mg.mul(a, b),
+            Reducer64::Wide(m) => mul_mod_u64(a, b, *m),
+        }
+    }
+}
+
+/// 64bitの法を持つModInt
+///
+/// `10^18`程度の法でもオーバーフローせずに演算できるので、
+/// Miller-RabinやPollardのrho法に使う
+///
+/// 法が奇数なら内部ではMontgomery表現で値を持つ
+///
+/// # Panic
+/// 異なるmod間で演算したとき
+#[snippet("montgomery")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModInt64 {
+    val: u64,
+    reducer: Reducer64,
+}
+
+#[snippet("montgomery")]
+impl ModInt64 {
+    pub fn new(n: u64, m: u64) -> Self {
+        Self::with_reducer(n, Reducer64::new(m))
+    }
+
+    /// 同じ法で何度も作るときは`Reducer64`を使い回す
+    pub fn with_reducer(n: u64, reducer: Reducer64) -> Self {
+        Self {
+            val: reducer.encode(n),
+            reducer,
+        }
+    }
+
+    /// 同じ法で値`n`を作る
+    pub fn rebuild(&self, n: u64) -> Self {
+        Self::with_reducer(n, self.reducer)
+    }
+
+    pub fn get(&self) -> u64 {
+        self.reducer.decode(self.val)
+    }
+
+    pub fn get_mod(&self) -> u64 {
+        self.reducer.modulus()
+    }
+
+    pub fn reducer(&self) -> Reducer64 {
+        self.reducer
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.val == 0
+    }
+
+    /// 二分累乗法
+    ///
+    /// `O(log exp)`
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut res = self.rebuild(1);
+        let mut base = self;
+        while exp > 0 {
+            if exp & 1 != 0 {
+                res *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        res
+    }
+
+    /// 乗法逆元
+    ///
+    /// 存在しなければ`None`
+    pub fn inv(self) -> Option<Self> {
+        let m = self.get_mod() as i128;
+        let (mut r0, mut r1) = (self.get() as i128, m);
+        let (mut x0, mut x1) = (1i128, 0i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            r0 -= q * r1;
+            std::mem::swap(&mut r0, &mut r1);
+            x0 -= q * x1;
+            std::mem::swap(&mut x0, &mut x1);
+        }
+        if r0 != 1 {
+            return None;
+        }
+        Some(self.rebuild(x0.rem_euclid(m) as u64))
+    }
+
+    fn check_mod_eq(&self, rhs: &Self) {
+        if self.reducer != rhs.reducer {
+            panic!("cannot operate these values because they have different modulo number")
+        }
+    }
+}
+
+#[snippet("montgomery")]
+impl fmt::Display for ModInt64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+#[snippet("montgomery")]
+impl Add for ModInt64 {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        self.check_mod_eq(&rhs);
+        let m = self.get_mod();
+        let (v, overflow) = self.val.overflowing_add(rhs.val);
+        Self {
+            val: if overflow || v >= m {
+                v.wrapping_sub(m)
+            } else {
+                v
+            },
+            reducer: self.reducer,
+        }
+    }
+}
+
+#[snippet("montgomery")]
+impl Sub for ModInt64 {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self.check_mod_eq(&rhs);
+        let val = if self.val >= rhs.val {
+            self.val - rhs.val
+        } else {
+            self.val.wrapping_sub(rhs.val).wrapping_add(self.get_mod())
+        };
+        Self {
+            val,
+            reducer: self.reducer,
+        }
+    }
+}
+
+#[snippet("montgomery")]
+impl Mul for ModInt64 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.check_mod_eq(&rhs);
+        Self {
+            val: self.reducer.mul(self.val, rhs.val),
+            reducer: self.reducer,
+        }
+    }
+}
+
+#[snippet("montgomery")]
+impl Div for ModInt64 {
+    type Output = Self;
+    /// # Panic
+    /// `rhs`の逆元が存在しないとき
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv().expect("the inverse does not exist")
+    }
+}
+
+#[snippet("montgomery")]
+impl Neg for ModInt64 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.rebuild(0) - self
+    }
+}
+
+#[snippet("montgomery")]
+macro_rules! impl_modint64_ops {
+    ($(($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident)),*) => {
+        $(
+            impl $OpAssign for ModInt64 {
+                fn $op_assign(&mut self, rhs: Self) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+            impl $Op<u64> for ModInt64 {
+                type Output = Self;
+                fn $op(self, rhs: u64) -> Self {
+                    $Op::$op(self, self.rebuild(rhs))
+                }
+            }
+            impl $OpAssign<u64> for ModInt64 {
+                fn $op_assign(&mut self, rhs: u64) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("montgomery")]
+impl_modint64_ops!(
+    (Add, add, AddAssign, add_assign),
+    (Sub, sub, SubAssign, sub_assign),
+    (Mul, mul, MulAssign, mul_assign),
+    (Div, div, DivAssign, div_assign)
+);
+
+#[test]
+fn montgomery_test() {
+    let moduli = [
+        1u64,
+        3,
+        998_244_353,
+        1_000_000_000_000_000_003,
+        (1 << 61) - 1,
+        std::u64::MAX,
+    ];
+    let values = [
+        0u64,
+        1,
+        2,
+        12345,
+        1 << 40,
+        999_999_999_999_999_989,
+        std::u64::MAX - 1,
+    ];
+    for &m in &moduli {
+        let mg = Montgomery64::new(m);
+        for &a in &values {
+            assert_eq!(mg.from_mont(mg.to_mont(a)), a % m);
+            for &b in &values {
+                let prod = mg.from_mont(mg.mul(mg.to_mont(a), mg.to_mont(b)));
+                assert_eq!(prod, mul_mod_u64(a % m, b % m, m));
+            }
+        }
+    }
+}
+
+#[test]
+fn modint64_test() {
+    for &m in &[1_000_000_000_000_000_003u64, 1 << 62, 10] {
+        let a = ModInt64::new(m - 1, m);
+        let b = ModInt64::new(m - 2, m);
+        assert_eq!((a + b).get(), m - 3 % m);
+        assert_eq!((b - a).get(), m - 1);
+        assert_eq!((a * b).get(), 2 % m);
+        assert_eq!((-a).get(), 1 % m);
+        assert_eq!((a * 3).get(), mul_mod_u64(m - 1, 3, m));
+        assert_eq!(a.pow(5).get(), pow_mod_u64(m - 1, 5, m));
+    }
+
+    // Fermatの小定理
+    let p = 1_000_000_000_000_000_003u64;
+    let x = ModInt64::new(123_456_789_123_456_789, p);
+    assert_eq!(x.pow(p - 1).get(), 1);
+    let y = ModInt64::new(123_456_789_123_456_789, p);
+    assert_eq!((x / y).get(), 1);
+    assert_eq!((x.inv().unwrap() * x).get(), 1);
+
+    assert!(ModInt64::new(4, 1 << 62).inv().is_none());
+    assert_eq!(
+        ModInt64::new(3, 1 << 62).inv().unwrap().get() * 3 % (1 << 62),
+        1
+    );
+    assert_eq!(format!("{}", ModInt64::new(15, 7)), "1");
+}
+
+#[test]
+#[should_panic]
+fn modint64_different_mod_test() {
+    let _ = ModInt64::new(1, 7) + ModInt64::new(1, 11);
+}
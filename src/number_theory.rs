@@ -0,0 +1,229 @@
+use cargo_snippet::snippet;
+
+/// `a^exp mod m`
+///
+/// 掛け算は`u128`を経由するので`m < 2^64`ならオーバーフローしない
+///
+/// `O(log exp)`
+#[snippet("number_theory")]
+pub fn pow_mod(a: u64, mut exp: u64, m: u64) -> u64 {
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 % m as u128) as u64;
+    let mut res = 1 % m;
+    let mut base = a % m;
+    while exp > 0 {
+        if exp & 1 != 0 {
+            res = mul(res, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    res
+}
+
+/// 拡張ユークリッドの互除法
+///
+/// `a * x + b * y = g`を満たす`(g, x, y)`を返す、ただし`g = gcd(a, b) >= 0`
+///
+/// `|x| <= |b / g|`, `|y| <= |a / g|`
+///
+/// `O(log min(|a|, |b|))`
+#[snippet("number_theory")]
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1i64, 0i64);
+    let (mut y0, mut y1) = (0i64, 1i64);
+    while r1 != 0 {
+        let quot = r0 / r1;
+        let next = (r0 - quot * r1, x0 - quot * x1, y0 - quot * y1);
+        r0 = r1;
+        x0 = x1;
+        y0 = y1;
+        r1 = next.0;
+        x1 = next.1;
+        y1 = next.2;
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// `a * x ≡ 1 (mod m)`となる`0 <= x < m`
+///
+/// `gcd(a, m) != 1`で逆元が存在しなければ`None`
+///
+/// # Panic
+/// `m < 1`のとき
+#[snippet("number_theory")]
+pub fn inv_mod(a: i64, m: i64) -> Option<i64> {
+    assert!(m >= 1, "modulo number must be positive");
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    if g != 1 {
+        None
+    } else {
+        Some(x.rem_euclid(m))
+    }
+}
+
+/// `a, b < 2^63`, `n, m <= 2^63`の範囲で、途中の`a * n + b`などは`u128`に収まる
+///
+/// 答えは`2^64`を法として求める
+#[snippet("number_theory")]
+fn floor_sum_unsigned(mut n: u128, mut m: u128, mut a: u128, mut b: u128) -> u64 {
+    let mut ans = 0u64;
+    loop {
+        if a >= m {
+            let tri = (n * n.saturating_sub(1) / 2) as u64;
+            ans = ans.wrapping_add(tri.wrapping_mul((a / m) as u64));
+            a %= m;
+        }
+        if b >= m {
+            ans = ans.wrapping_add((n as u64).wrapping_mul((b / m) as u64));
+            b %= m;
+        }
+        let y_max = a * n + b;
+        if y_max < m {
+            break;
+        }
+        n = y_max / m;
+        b = y_max % m;
+        std::mem::swap(&mut m, &mut a);
+    }
+    ans
+}
+
+/// `Σ_{i = 0}^{n - 1} floor((a * i + b) / m)`
+///
+/// `a`, `b`は負でもよい
+///
+/// `O(log m)`
+///
+/// # Panic
+/// `n < 0 || m < 1`のとき
+#[snippet("number_theory")]
+pub fn floor_sum(n: i64, m: i64, a: i64, b: i64) -> i64 {
+    assert!(n >= 0 && m >= 1);
+    let mut ans = 0i64;
+    // 負の係数は非負に直して、その差の分を先に引いておく
+    let (mut a_pos, mut b_pos) = (a, b);
+    if a < 0 {
+        a_pos = a.rem_euclid(m);
+        let tri = (n as i128 * (n as i128 - 1).max(0) / 2) as i64;
+        ans = ans.wrapping_sub(tri.wrapping_mul((a_pos - a) / m));
+    }
+    if b < 0 {
+        b_pos = b.rem_euclid(m);
+        ans = ans.wrapping_sub(n.wrapping_mul((b_pos - b) / m));
+    }
+    ans.wrapping_add(floor_sum_unsigned(n as u128, m as u128, a_pos as u128, b_pos as u128) as i64)
+}
+
+/// 一次不定方程式`a * x + b * y = c`を解く
+///
+/// 返り値を`((x0, y0), (dx, dy))`とすると、解は任意の整数`k`について
+/// `(x0 + k * dx, y0 + k * dy)`と表せて、これで全てである
+///
+/// `dx != 0`のとき`0 <= x0 < |dx|`となるものを返す
+///
+/// 解が存在しなければ`None`
+///
+/// # Panic
+/// `a == 0 && b == 0`のとき
+#[snippet("number_theory")]
+pub fn linear_diophantine(a: i64, b: i64, c: i64) -> Option<((i64, i64), (i64, i64))> {
+    assert!(a != 0 || b != 0, "a and b must not be both zero");
+    let (g, coef_a, coef_b) = ext_gcd(a, b);
+    if c % g != 0 {
+        return None;
+    }
+    let (dx, dy) = (b / g, -a / g);
+    let scale = (c / g) as i128;
+    if dx == 0 {
+        // b == 0 なので x は一意
+        return Some(((c / a, (coef_b as i128 * scale) as i64), (0, dy)));
+    }
+    let x0 = (coef_a as i128 * scale).rem_euclid(dx.abs() as i128) as i64;
+    let y0 = ((c as i128 - a as i128 * x0 as i128) / b as i128) as i64;
+    Some(((x0, y0), (dx, dy)))
+}
+
+#[test]
+fn ext_gcd_test() {
+    for a in -30..30i64 {
+        for b in -30..30i64 {
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, num_integer::gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    assert_eq!(inv_mod(3, 7), Some(5));
+    assert_eq!(inv_mod(-3, 7), Some(2));
+    assert_eq!(inv_mod(4, 6), None);
+    assert_eq!(inv_mod(5, 1), Some(0));
+    for m in 1..50 {
+        for a in 0..m {
+            let expected = (0..m).find(|&x| a * x % m == 1 % m);
+            assert_eq!(inv_mod(a, m), expected);
+        }
+    }
+
+    assert_eq!(pow_mod(2, 10, 1_000_000_007), 1024);
+    assert_eq!(pow_mod(3, 0, 1), 0);
+    let m = (1 << 61) - 1;
+    assert_eq!(pow_mod(m - 1, 3, m), m - 1);
+}
+
+#[test]
+fn floor_sum_test() {
+    for n in 0..15 {
+        for m in 1..8 {
+            for a in -10..10 {
+                for b in -10..10 {
+                    let expected = (0..n)
+                        .map(|i| num_integer::Integer::div_floor(&(a * i + b), &m))
+                        .sum::<i64>();
+                    assert_eq!(floor_sum(n, m, a, b), expected);
+                }
+            }
+        }
+    }
+    assert_eq!(
+        floor_sum(1_000_000_000, 1_000_000_000, 999_999_999, 999_999_999),
+        499_999_999_500_000_000
+    );
+    // 途中の`a * n + b`や`n * (n - 1)`が64bitに収まらない
+    let big = 1_000_000_000_000_000_000;
+    assert_eq!(floor_sum(100, big, big - 1, 0), 4851);
+    assert_eq!(floor_sum(100, big, -(big - 1), 0), -4950);
+    assert_eq!(
+        floor_sum(4_000_000_000, 1, -1, 0),
+        -7_999_999_998_000_000_000
+    );
+}
+
+#[test]
+fn linear_diophantine_test() {
+    for a in -12..12i64 {
+        for b in -12..12i64 {
+            if a == 0 && b == 0 {
+                continue;
+            }
+            for c in -20..20i64 {
+                let exists = (-50..50).any(|x| (-50..50).any(|y| a * x + b * y == c));
+                match linear_diophantine(a, b, c) {
+                    None => assert!(!exists),
+                    Some(((x0, y0), (dx, dy))) => {
+                        assert!(exists);
+                        for k in -3..3 {
+                            assert_eq!(a * (x0 + k * dx) + b * (y0 + k * dy), c);
+                        }
+                        assert_eq!(a * dx + b * dy, 0);
+                        assert_eq!(num_integer::gcd(dx, dy), 1);
+                    }
+                }
+            }
+        }
+    }
+}
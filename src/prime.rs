@@ -1,2 +1,4 @@
+pub mod pollard_rho;
+pub mod prime_counting;
 pub mod sieve;
 pub mod trial_division;
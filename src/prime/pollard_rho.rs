@@ -0,0 +1,257 @@
+use cargo_snippet::snippet;
+
+use super::trial_division::divisors_from_factors;
+use crate::montgomery::Montgomery64;
+
+/// Miller-Rabin法による決定的な素数判定
+///
+/// `n < 2^64`の範囲では、7つの底で判定すれば誤りがない
+///
+/// `O(log n)`
+#[snippet("pollard_rho")]
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    if n < 37 * 37 {
+        return true;
+    }
+
+    let mont = Montgomery64::new(n);
+    let one = mont.to_mont(1);
+    let minus_one = mont.to_mont(n - 1);
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let pow = |base: u64, mut exp: u64| {
+        let mut res = one;
+        let mut cur = base;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = mont.mul(res, cur);
+            }
+            cur = mont.mul(cur, cur);
+            exp >>= 1;
+        }
+        res
+    };
+
+    [2, 325, 9375, 28178, 450_775, 9_780_504, 1_795_265_022]
+        .iter()
+        .filter(|&&a| a % n != 0)
+        .all(|&a| {
+            let mut x = pow(mont.to_mont(a), d);
+            if x == one || x == minus_one {
+                return true;
+            }
+            for _ in 1..s {
+                x = mont.mul(x, x);
+                if x == minus_one {
+                    return true;
+                }
+            }
+            false
+        })
+}
+
+/// 合成数`n`の非自明な約数を一つ求める
+///
+/// Pollard's rho法のBrentによる変種で、`gcd`をまとめて取る
+///
+/// 期待`O(n^(1/4))`回の乗算
+#[snippet("pollard_rho")]
+fn find_factor(n: u64) -> u64 {
+    if n % 2 == 0 {
+        return 2;
+    }
+    let mont = Montgomery64::new(n);
+    let add = |lhs: u64, rhs: u64| {
+        let (sum, overflow) = lhs.overflowing_add(rhs);
+        if overflow || sum >= n {
+            sum.wrapping_sub(n)
+        } else {
+            sum
+        }
+    };
+    let diff = |lhs: u64, rhs: u64| if lhs > rhs { lhs - rhs } else { rhs - lhs };
+    const BATCH: u64 = 128;
+
+    for c in 1.. {
+        let shift = mont.to_mont(c);
+        let step = |v: u64| add(mont.mul(v, v), shift);
+        let mut fast = mont.to_mont(2);
+        let mut saved = fast;
+        let mut backup = fast;
+        let mut prod = mont.to_mont(1);
+        let mut g = 1;
+        let mut len = 1;
+        while g == 1 {
+            saved = fast;
+            for _ in 0..len {
+                fast = step(fast);
+            }
+            let mut done = 0;
+            while done < len && g == 1 {
+                backup = fast;
+                for _ in 0..std::cmp::min(BATCH, len - done) {
+                    fast = step(fast);
+                    prod = mont.mul(prod, diff(saved, fast));
+                }
+                // Montgomery表現のままでも、Rとnは互いに素なのでgcdは変わらない
+                g = num_integer::gcd(prod, n);
+                done += BATCH;
+            }
+            len <<= 1;
+        }
+        if g == n {
+            // まとめすぎて全ての因数を含んでしまったら一つずつやり直す
+            loop {
+                backup = step(backup);
+                g = num_integer::gcd(diff(saved, backup), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+#[snippet("pollard_rho")]
+fn factorization_inner(n: u64, res: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        res.push(n);
+        return;
+    }
+    let d = find_factor(n);
+    factorization_inner(d, res);
+    factorization_inner(n / d, res);
+}
+
+/// Pollard's rho法による素因数分解
+///
+/// `trial_division::factorization`と同じく、`(素数, 指数)`を素数の昇順に並べて返す
+///
+/// 期待`O(n^(1/4) log n)`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("pollard_rho")]
+pub fn factorization(mut n: u64) -> Vec<(u64, usize)> {
+    assert!(n > 0, "cannot factorize 0");
+    let mut primes = Vec::new();
+    // 小さい素因数は先に試し割りする
+    for p in 2..100 {
+        while n % p == 0 {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    factorization_inner(n, &mut primes);
+    primes.sort();
+
+    let mut res: Vec<(u64, usize)> = Vec::new();
+    for p in primes {
+        match res.last_mut() {
+            Some(last) if last.0 == p => last.1 += 1,
+            _ => res.push((p, 1)),
+        }
+    }
+    res
+}
+
+/// 素因数分解の結果から、全ての約数を昇順に列挙する
+///
+/// `O(d(n) log d(n))`
+#[snippet("pollard_rho")]
+pub fn divisors(factors: &[(u64, usize)]) -> Vec<u64> {
+    divisors_from_factors(factors)
+}
+
+#[test]
+fn miller_rabin_test() {
+    use super::trial_division;
+
+    for n in 0..10000 {
+        assert_eq!(is_prime(n), trial_division::is_prime(n as usize), "{}", n);
+    }
+    assert!(is_prime(998_244_353));
+    assert!(is_prime(1_000_000_007));
+    assert!(is_prime((1 << 61) - 1));
+    assert!(is_prime(18_446_744_073_709_551_557));
+    // カーマイケル数と強擬素数
+    assert!(!is_prime(561));
+    assert!(!is_prime(3_215_031_751));
+    assert!(!is_prime(3_825_123_056_546_413_051));
+    assert!(!is_prime(1_000_000_007 * 998_244_353));
+    assert!(!is_prime(std::u64::MAX));
+}
+
+#[test]
+fn pollard_rho_test() {
+    use super::trial_division;
+
+    for n in 1..2000 {
+        let expected = trial_division::factorization(n as usize)
+            .into_iter()
+            .map(|(p, e)| (p as u64, e))
+            .collect::<Vec<_>>();
+        assert_eq!(factorization(n), expected);
+    }
+    assert_eq!(
+        factorization(1_000_000_000_000_000_000),
+        vec![(2, 18), (5, 18)]
+    );
+    assert_eq!(
+        factorization(1_000_000_007 * 998_244_353),
+        vec![(998_244_353, 1), (1_000_000_007, 1)]
+    );
+    assert_eq!(
+        factorization(std::u64::MAX),
+        vec![
+            (3, 1),
+            (5, 1),
+            (17, 1),
+            (257, 1),
+            (641, 1),
+            (65537, 1),
+            (6_700_417, 1)
+        ]
+    );
+    assert_eq!(
+        factorization(999_999_999_999_999_989),
+        vec![(999_999_999_999_999_989, 1)]
+    );
+    assert_eq!(
+        factorization(4_611_686_014_132_420_609),
+        vec![(2_147_483_647, 2)]
+    );
+}
+
+#[test]
+#[should_panic]
+fn factorization_zero_test() {
+    factorization(0);
+}
+
+#[test]
+fn divisors_test() {
+    assert_eq!(divisors(&factorization(1)), vec![1]);
+    assert_eq!(divisors(&factorization(12)), vec![1, 2, 3, 4, 6, 12]);
+    assert_eq!(divisors(&factorization(720_720)).len(), 240);
+    let n = 1_000_000_007 * 998_244_353;
+    assert_eq!(
+        divisors(&factorization(n)),
+        vec![1, 998_244_353, 1_000_000_007, n]
+    );
+}
@@ -0,0 +1,150 @@
+use cargo_snippet::snippet;
+
+use super::sieve::{isqrt, Sieve};
+use std::ops::{Mul, Sub};
+
+/// `floor(n / k)`の形の全ての`v`について、`v`以下の素数`p`の`f(p)`の和を持つテーブル
+#[snippet("prime_counting")]
+#[derive(Debug, Clone)]
+pub struct PrimeSumTable<T> {
+    n: usize,
+    sqrt: usize,
+    /// `small[v]`は`v <= √n`での値
+    small: Vec<T>,
+    /// `large[k]`は`v = floor(n / k) > √n`での値
+    large: Vec<T>,
+}
+
+#[snippet("prime_counting")]
+impl<T: Copy> PrimeSumTable<T> {
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// `v`以下の素数`p`についての`f(p)`の和
+    ///
+    /// `v`はある`k`について`floor(n / k)`と書ける値である必要がある
+    pub fn get(&self, v: usize) -> T {
+        assert!(v <= self.n);
+        if v <= self.sqrt {
+            self.small[v]
+        } else {
+            self.large[self.n / v]
+        }
+    }
+}
+
+/// Lucy_Hedgehogの方法で、完全乗法的関数`f`の素数上の和を求める
+///
+/// `prefix(v)`には`Σ_{i = 2}^{v} f(i)`を、`f(p)`には素数`p`での値を与える
+///
+/// `floor(n / k)`の形の全ての`v`について`Σ_{p <= v} f(p)`が求まるので、
+/// min_25篩などの前計算に使える
+///
+/// `O(n^(3/4) / log n)`
+#[snippet("prime_counting")]
+pub fn prime_sum_table<T, F, G>(n: usize, prefix: F, f: G) -> PrimeSumTable<T>
+where
+    T: Copy + Sub<Output = T> + Mul<Output = T>,
+    F: Fn(usize) -> T,
+    G: Fn(usize) -> T,
+{
+    let sqrt = isqrt(n);
+    let mut small = (0..=sqrt).map(&prefix).collect::<Vec<_>>();
+    let mut large = (0..=sqrt)
+        .map(|k| if k == 0 { prefix(0) } else { prefix(n / k) })
+        .collect::<Vec<_>>();
+
+    for p in Sieve::new_linear(sqrt).primes(sqrt) {
+        // p未満の素数についての和
+        let base = small[p - 1];
+        let fp = f(p);
+        let p2 = p * p;
+        for k in 1..=std::cmp::min(sqrt, n / p2) {
+            let kp = k * p;
+            let sub = if kp <= sqrt { large[kp] } else { small[n / kp] };
+            large[k] = large[k] - fp * (sub - base);
+        }
+        for v in (p2..=sqrt).rev() {
+            small[v] = small[v] - fp * (small[v / p] - base);
+        }
+    }
+
+    PrimeSumTable {
+        n,
+        sqrt,
+        small,
+        large,
+    }
+}
+
+/// `n`以下の素数の個数
+///
+/// `n = 10^11`でも1秒程度
+///
+/// `O(n^(3/4) / log n)`
+#[snippet("prime_counting")]
+pub fn prime_pi(n: usize) -> usize {
+    prime_sum_table(n, |v| v.saturating_sub(1), |_| 1).get(n)
+}
+
+/// `n`以下の素数の和
+///
+/// `O(n^(3/4) / log n)`
+#[snippet("prime_counting")]
+pub fn prime_sum(n: usize) -> u128 {
+    prime_sum_table(
+        n,
+        |v| {
+            let v = v as u128;
+            (v * (v + 1) / 2).saturating_sub(1)
+        },
+        |p| p as u128,
+    )
+    .get(n)
+}
+
+#[test]
+fn prime_counting_test() {
+    let sieve = Sieve::new(3000);
+    for n in 0..3000 {
+        let primes = sieve.primes(n);
+        assert_eq!(prime_pi(n), primes.len());
+        assert_eq!(
+            prime_sum(n),
+            primes.iter().map(|&p| p as u128).sum::<u128>()
+        );
+    }
+
+    // floor(n / k)の形の値は全て引ける
+    let n = 2999;
+    let table = prime_sum_table(n, |v| v.saturating_sub(1), |_| 1);
+    for k in 1..=n {
+        assert_eq!(table.get(n / k), sieve.primes(n / k).len());
+    }
+
+    assert_eq!(prime_pi(1_000_000_000), 50_847_534);
+    assert_eq!(prime_sum(2_000_000), 142_913_828_922);
+}
+
+#[test]
+fn prime_sum_table_modint_test() {
+    use crate::static_modint::ModInt998244353 as Mint;
+
+    // 素数の二乗和
+    let n = 1000;
+    let table = prime_sum_table(
+        n,
+        |v| {
+            let v = Mint::new(v as i64);
+            v * (v + Mint::new(1)) * (v * Mint::new(2) + Mint::new(1)) / Mint::new(6) - Mint::new(1)
+        },
+        |p| Mint::new(p as i64) * Mint::new(p as i64),
+    );
+    let expected = Sieve::new(n)
+        .primes(n)
+        .iter()
+        .map(|&p| Mint::new((p * p) as i64))
+        .fold(Mint::new(0), |acc, x| acc + x);
+    assert_eq!(table.get(n), expected);
+}
@@ -3,7 +3,10 @@ use cargo_snippet::snippet;
 pub use sieve::*;
 #[snippet(name = "sieve", prefix = "pub use sieve::*;")]
 pub mod sieve {
+    use crate::prime::trial_division::divisors_from_factors;
+    use num_traits::{One, Zero};
     use std::collections::BTreeMap;
+    use std::ops::Mul;
     /// 初期化の際にだけエラストテネスの篩を使って素数のリストを生成
     ///
     /// `O(n)`
@@ -53,6 +56,40 @@ pub mod sieve {
             }
         }
 
+        /// 線形篩で初期化する
+        ///
+        /// 各合成数を最小素因数で一度だけ振り落とす
+        ///
+        /// `O(n)`
+        pub fn new_linear(n: usize) -> Self {
+            let mut spf = vec![0; n + 1];
+            let mut is_prime = vec![false; n + 1];
+            let mut primes = Vec::new();
+
+            for i in 2..=n {
+                if spf[i] == 0 {
+                    spf[i] = i;
+                    is_prime[i] = true;
+                    primes.push(i);
+                }
+                let spf_i = spf[i];
+                for &p in primes.iter().take_while(|&&p| p <= spf_i && i * p <= n) {
+                    spf[i * p] = p;
+                }
+            }
+            // 0と1は自分自身を入れておく(`new`と揃える)
+            for (i, x) in spf.iter_mut().enumerate().take(2) {
+                *x = i;
+            }
+
+            Self {
+                size: n,
+                spf,
+                is_prime,
+                primes,
+            }
+        }
+
         /// 自分自身の素数リストの有効な範囲
         pub fn size(&self) -> usize {
             self.size
@@ -75,6 +112,97 @@ pub mod sieve {
         pub fn is_prime(&self, n: usize) -> bool {
             self.is_prime[n]
         }
+
+        /// 最小素因数を使った素因数分解
+        ///
+        /// `(素数, 指数)`を素数の昇順に並べて返す
+        ///
+        /// `O(log x)`
+        ///
+        /// # Panic
+        /// `x > self.size`のとき
+        pub fn factorize(&self, mut x: usize) -> Vec<(usize, usize)> {
+            assert!(x <= self.size);
+            let mut res: Vec<(usize, usize)> = Vec::new();
+            while x > 1 {
+                let p = self.spf[x];
+                match res.last_mut() {
+                    Some(last) if last.0 == p => last.1 += 1,
+                    _ => res.push((p, 1)),
+                }
+                x /= p;
+            }
+            res
+        }
+
+        /// `x`の約数を昇順に列挙する
+        ///
+        /// # Panic
+        /// `x > self.size`のとき
+        pub fn divisors(&self, x: usize) -> Vec<usize> {
+            divisors_from_factors(&self.factorize(x))
+        }
+
+        /// 乗法的関数`f`の`0..=n`での値のテーブル
+        ///
+        /// `f`には素数冪での値`f(p^k)`を`pk(p, k)`として与える
+        ///
+        /// `f(1) = 1`で、`f(0)`は`0`とする
+        ///
+        /// `O(n)`回の`pk`の呼び出しと乗算
+        pub fn multiplicative_table<T, F>(&self, pk: F) -> Vec<T>
+        where
+            T: Copy + Zero + One + Mul<Output = T>,
+            F: Fn(usize, usize) -> T,
+        {
+            let n = self.size;
+            let mut res = vec![T::zero(); n + 1];
+            if n >= 1 {
+                res[1] = T::one();
+            }
+            // prime_pow[i]は`i`を割り切る最小素因数の最大冪、exp[i]はその指数
+            let mut prime_pow = vec![1; n + 1];
+            let mut exp = vec![0; n + 1];
+            for i in 2..=n {
+                let p = self.spf[i];
+                let j = i / p;
+                if self.spf[j] == p {
+                    prime_pow[i] = prime_pow[j] * p;
+                    exp[i] = exp[j] + 1;
+                } else {
+                    prime_pow[i] = p;
+                    exp[i] = 1;
+                }
+                res[i] = if prime_pow[i] == i {
+                    pk(p, exp[i])
+                } else {
+                    res[i / prime_pow[i]] * res[prime_pow[i]]
+                };
+            }
+            res
+        }
+
+        /// オイラーのφ関数のテーブル
+        ///
+        /// `phi[i]`は`1..=i`のうち`i`と互いに素なものの数
+        pub fn euler_phi_table(&self) -> Vec<usize> {
+            self.multiplicative_table(|p, k| (p - 1) * p.pow(k as u32 - 1))
+        }
+
+        /// メビウス関数のテーブル
+        pub fn mobius_table(&self) -> Vec<i64> {
+            self.multiplicative_table(|_, k| if k == 1 { -1 } else { 0 })
+        }
+
+        /// 約数の個数のテーブル
+        pub fn divisor_count_table(&self) -> Vec<usize> {
+            self.multiplicative_table(|_, k| k + 1)
+        }
+
+        /// 約数の総和のテーブル
+        pub fn divisor_sum_table(&self) -> Vec<usize> {
+            self.multiplicative_table(|p, k| (p.pow(k as u32 + 1) - 1) / (p - 1))
+        }
     }
 
     /// Seiveテーブルを用いた素因数分解
@@ -105,6 +233,86 @@ pub mod sieve {
         factorizations_with_sieve_inner(sieve, n / d, res);
     }
 
+    /// `floor(√n)`
+    pub(crate) fn isqrt(n: usize) -> usize {
+        let mut s = (n as f64).sqrt() as usize;
+        while s * s > n {
+            s -= 1;
+        }
+        while (s + 1) * (s + 1) <= n {
+            s += 1;
+        }
+        s
+    }
+
+    /// 区間篩
+    ///
+    /// 閉区間`[l, r]`に含まれる素数を昇順に列挙する
+    ///
+    /// `√r`以下の素数を`Sieve`で求めてから区間の中だけを篩うので、
+    /// メモリは`O(√r + (r - l))`
+    ///
+    /// `O(√r + (r - l) log log r)`
+    pub fn segmented_primes(l: usize, r: usize) -> Vec<usize> {
+        if l > r || r < 2 {
+            return Vec::new();
+        }
+        let base = Sieve::new(isqrt(r));
+        let mut is_prime = vec![true; r - l + 1];
+        for &p in &base.primes {
+            let start = std::cmp::max(p * p, (l + p - 1) / p * p);
+            for j in (start..=r).step_by(p) {
+                is_prime[j - l] = false;
+            }
+        }
+        is_prime
+            .iter()
+            .enumerate()
+            .filter(|&(i, &b)| b && l + i >= 2)
+            .map(|(i, _)| l + i)
+            .collect()
+    }
+
+    /// 区間篩による素因数分解
+    ///
+    /// 閉区間`[l, r]`の各整数`x`について、`x`の素因数分解を`(素数, 指数)`の昇順で求める
+    ///
+    /// 返り値の`i`番目が`l + i`の素因数分解(`0`と`1`は空)
+    ///
+    /// `O(√r + (r - l) log r)`
+    pub fn segmented_factorization(l: usize, r: usize) -> Vec<Vec<(usize, usize)>> {
+        if l > r {
+            return Vec::new();
+        }
+        if r < 2 {
+            return vec![Vec::new(); r - l + 1];
+        }
+        let base = Sieve::new(isqrt(r));
+        let mut rest = (l..=r).collect::<Vec<_>>();
+        let mut res = vec![Vec::new(); r - l + 1];
+        for &p in &base.primes {
+            // 0は素因数分解しない
+            let start = std::cmp::max(p, (l + p - 1) / p * p);
+            for j in (start..=r).step_by(p) {
+                let mut e = 0;
+                while rest[j - l] % p == 0 {
+                    rest[j - l] /= p;
+                    e += 1;
+                }
+                if e > 0 {
+                    res[j - l].push((p, e));
+                }
+            }
+        }
+        // 残りは√rより大きい素因数
+        for (f, &x) in res.iter_mut().zip(rest.iter()) {
+            if x > 1 {
+                f.push((x, 1));
+            }
+        }
+        res
+    }
+
     #[test]
     fn sieve_test() {
         let sieve = Sieve::new(10);
@@ -124,4 +332,88 @@ pub mod sieve {
         // let f = factorizations_with_sieve(&sieve, 1000000007);
         // assert_eq!(f, btreemap![1000000007 => 1]);
     }
+
+    #[test]
+    fn linear_sieve_test() {
+        let n = 1000;
+        let (eratos, linear) = (Sieve::new(n), Sieve::new_linear(n));
+        assert_eq!(eratos.primes, linear.primes);
+        assert_eq!(eratos.is_prime, linear.is_prime);
+        assert_eq!(eratos.spf, linear.spf);
+        assert_eq!(Sieve::new_linear(1).primes(1), vec![]);
+    }
+
+    #[test]
+    fn multiplicative_table_test() {
+        let n = 300;
+        let sieve = Sieve::new_linear(n);
+        let gcd = |mut a: usize, mut b: usize| {
+            while b != 0 {
+                a %= b;
+                std::mem::swap(&mut a, &mut b);
+            }
+            a
+        };
+
+        let phi = sieve.euler_phi_table();
+        let mobius = sieve.mobius_table();
+        let count = sieve.divisor_count_table();
+        let sum = sieve.divisor_sum_table();
+        for i in 1..=n {
+            let divs = (1..=i).filter(|d| i % d == 0).collect::<Vec<_>>();
+            assert_eq!(sieve.divisors(i), divs);
+            assert_eq!(phi[i], (1..=i).filter(|&j| gcd(i, j) == 1).count());
+            assert_eq!(count[i], divs.len());
+            assert_eq!(sum[i], divs.iter().sum::<usize>());
+            let square_free = (2..=i).all(|d| i % (d * d) != 0);
+            let expected = if square_free {
+                if sieve.factorize(i).len() % 2 == 0 {
+                    1
+                } else {
+                    -1
+                }
+            } else {
+                0
+            };
+            assert_eq!(mobius[i], expected);
+        }
+        assert_eq!(sieve.factorize(280), vec![(2, 3), (5, 1), (7, 1)]);
+        assert_eq!(sieve.factorize(1), vec![]);
+    }
+
+    #[test]
+    fn segmented_sieve_test() {
+        let sieve = Sieve::new(2000);
+        for &(l, r) in &[
+            (0, 0),
+            (0, 100),
+            (1, 1),
+            (2, 2),
+            (90, 1000),
+            (1500, 2000),
+            (5, 4),
+        ] {
+            let expected = (l..=r).filter(|&x| sieve.is_prime(x)).collect::<Vec<_>>();
+            assert_eq!(segmented_primes(l, r), expected);
+        }
+
+        let l = 1_000_000_000_000;
+        let primes = segmented_primes(l, l + 1000);
+        assert_eq!(primes.first(), Some(&1_000_000_000_039));
+        assert_eq!(primes.len(), 37);
+
+        let facts = segmented_factorization(l, l + 100);
+        assert_eq!(facts[0], vec![(2, 12), (5, 12)]);
+        assert_eq!(facts[39], vec![(1_000_000_000_039, 1)]);
+        for (i, f) in facts.iter().enumerate() {
+            let x = f.iter().map(|&(p, e)| p.pow(e as u32)).product::<usize>();
+            assert_eq!(x, l + i);
+        }
+        assert_eq!(
+            segmented_factorization(0, 4),
+            vec![vec![], vec![], vec![(2, 1)], vec![(3, 1)], vec![(2, 2)]]
+        );
+        assert_eq!(segmented_factorization(0, 0), vec![vec![]]);
+        assert_eq!(segmented_factorization(0, 1), vec![vec![], vec![]]);
+    }
 }
@@ -34,6 +34,8 @@
 // }
 
 use cargo_snippet::snippet;
+use num_traits::One;
+use std::ops::Mul;
 
 /// 2以上で`x`の約数であるものの内最小であるものを求める
 #[snippet("trial_division")]
@@ -84,8 +86,17 @@ fn exp(n: &mut usize, d: usize) -> (usize, usize) {
     )
 }
 
+/// 試し割りによる素因数分解
+///
+/// `(素数, 指数)`を素数の昇順に並べて返す
+///
+/// `O(√n)`
+///
+/// # Panic
+/// `n == 0`のとき
 #[snippet("trial_division")]
 pub fn factorization(mut n: usize) -> Vec<(usize, usize)> {
+    assert!(n > 0, "cannot factorize 0");
     let mut primes = Vec::new();
     let (d, e) = exp(&mut n, 2);
     if e > 0 {
@@ -116,3 +127,144 @@ fn f_test() {
     let f = factorization(1000000007);
     assert_eq!(f, vec![(1000000007, 1)]);
 }
+
+/// オイラーのφ関数
+///
+/// `1..=n`のうち`n`と互いに素なものの数
+///
+/// `O(√n)`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("trial_division")]
+pub fn euler_phi(n: usize) -> usize {
+    factorization(n)
+        .iter()
+        .fold(n, |acc, &(p, _)| acc / p * (p - 1))
+}
+
+/// メビウス関数
+///
+/// `n`が平方因子を持てば`0`、そうでなければ素因数の個数が偶数なら`1`、奇数なら`-1`
+///
+/// `O(√n)`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("trial_division")]
+pub fn mobius(n: usize) -> i64 {
+    let f = factorization(n);
+    if f.iter().any(|&(_, e)| e > 1) {
+        0
+    } else if f.len() % 2 == 0 {
+        1
+    } else {
+        -1
+    }
+}
+
+/// 約数の個数
+///
+/// `O(√n)`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("trial_division")]
+pub fn divisor_count(n: usize) -> usize {
+    factorization(n).iter().map(|&(_, e)| e + 1).product()
+}
+
+/// 約数の総和
+///
+/// `1 + p + ... + p^e`を順に足していくので、`p^(e + 1)`は計算しない
+///
+/// `O(√n)`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("trial_division")]
+pub fn divisor_sum(n: usize) -> usize {
+    factorization(n)
+        .iter()
+        .map(|&(p, e)| {
+            let (mut sum, mut pow) = (1, 1);
+            for _ in 0..e {
+                pow *= p;
+                sum += pow;
+            }
+            sum
+        })
+        .product()
+}
+
+/// 素因数分解の結果`(素数, 指数)`の列から、全ての約数を昇順に列挙する
+///
+/// `pollard_rho`や`Sieve`の素因数分解の結果にも使える
+///
+/// `O(d(n) log d(n))`
+#[snippet("trial_division")]
+pub fn divisors_from_factors<T>(factors: &[(T, usize)]) -> Vec<T>
+where
+    T: Copy + Ord + One + Mul<Output = T>,
+{
+    let mut res = vec![T::one()];
+    for &(p, e) in factors {
+        let len = res.len();
+        let mut pow = T::one();
+        for _ in 0..e {
+            pow = pow * p;
+            for i in 0..len {
+                res.push(res[i] * pow);
+            }
+        }
+    }
+    res.sort();
+    res
+}
+
+/// 約数を昇順に列挙する
+///
+/// `O(√n + d(n) log d(n))`
+///
+/// # Panic
+/// `n == 0`のとき
+#[snippet("trial_division")]
+pub fn divisors(n: usize) -> Vec<usize> {
+    divisors_from_factors(&factorization(n))
+}
+
+#[test]
+fn multiplicative_functions_test() {
+    use super::sieve::Sieve;
+
+    let n = 500;
+    let sieve = Sieve::new_linear(n);
+    let (phi, mu) = (sieve.euler_phi_table(), sieve.mobius_table());
+    let (count, sum) = (sieve.divisor_count_table(), sieve.divisor_sum_table());
+    for i in 1..=n {
+        assert_eq!(euler_phi(i), phi[i]);
+        assert_eq!(mobius(i), mu[i]);
+        assert_eq!(divisor_count(i), count[i]);
+        assert_eq!(divisor_sum(i), sum[i]);
+        assert_eq!(divisors(i), sieve.divisors(i));
+    }
+
+    let big = 1_000_000_007 * 2 * 2 * 3;
+    assert_eq!(euler_phi(big), 1_000_000_006 * 4);
+    assert_eq!(mobius(big), 0);
+    assert_eq!(mobius(1_000_000_007 * 3), 1);
+    assert_eq!(divisor_count(big), 12);
+    assert_eq!(divisor_sum(big), 1_000_000_008 * 7 * 4);
+    assert_eq!(divisors(big).len(), 12);
+    assert_eq!(*divisors(big).last().unwrap(), big);
+
+    // 素因数が大きくても`p^(e + 1)`で溢れない
+    assert_eq!(divisor_sum(1_000_000_000_039), 1_000_000_000_040);
+    assert_eq!(divisor_sum(4_294_967_311 * 2), 4_294_967_312 * 3);
+}
+
+#[test]
+#[should_panic]
+fn euler_phi_zero_test() {
+    euler_phi(0);
+}
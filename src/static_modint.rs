@@ -0,0 +1,484 @@
+use cargo_snippet::snippet;
+
+use crate::number_theory::inv_mod;
+use num_traits::identities::{One, Zero};
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 型に紐付いたmod
+///
+/// `StaticModInt<M>`の`M`に使う
+#[snippet("static_modint")]
+pub trait Modulus: 'static + Copy + Eq + Ord + std::hash::Hash + fmt::Debug {
+    const VALUE: u32;
+    /// `VALUE`が素数であるか、割り算の方法を切り替えるのに使う
+    const IS_PRIME: bool;
+    /// NTTに使う`VALUE`の原始根
+    ///
+    /// `0`のままならNTTの初回に計算する
+    const PRIMITIVE_ROOT: u32 = 0;
+}
+
+/// `Modulus`を実装したマーカー型を定義するマクロ
+///
+/// 一行目に型名、
+/// 二行目にmod、
+/// 三行目にmodが素数であるかどうか、
+/// (省略可)四行目にNTTで使う原始根
+///
+/// ```example
+/// modulus_def! {
+///     Mod17,
+///     17,
+///     true,
+///     3
+/// }
+///
+/// type ModInt17 = StaticModInt<Mod17>;
+/// ```
+#[snippet("static_modint")]
+#[macro_export]
+macro_rules! modulus_def {
+    {
+        $M:ident,
+        $m:expr,
+        $is_prime:expr
+    } => {
+        modulus_def! {
+            $M,
+            $m,
+            $is_prime,
+            0
+        }
+    };
+    {
+        $M:ident,
+        $m:expr,
+        $is_prime:expr,
+        $root:expr
+    } => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $M {}
+
+        impl Modulus for $M {
+            const VALUE: u32 = $m;
+            const IS_PRIME: bool = $is_prime;
+            const PRIMITIVE_ROOT: u32 = $root;
+        }
+    };
+}
+
+#[snippet("static_modint")]
+modulus_def! {
+    Mod998244353,
+    998_244_353,
+    true,
+    3
+}
+
+#[snippet("static_modint")]
+modulus_def! {
+    Mod1000000007,
+    1_000_000_007,
+    true,
+    5
+}
+
+/// modを型パラメータに持つModInt
+///
+/// 値は`u32`一つだけを持ち、演算の際にmodの検査をしない
+#[snippet("static_modint")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaticModInt<M: Modulus> {
+    val: u32,
+    phantom: PhantomData<fn() -> M>,
+}
+
+#[snippet("static_modint")]
+pub type ModInt998244353 = StaticModInt<Mod998244353>;
+#[snippet("static_modint")]
+pub type ModInt1000000007 = StaticModInt<Mod1000000007>;
+
+/// 静的、動的なModIntに共通の操作
+///
+/// 畳み込みや形式的冪級数など、modの持ち方によらないアルゴリズムで使う
+#[snippet("static_modint")]
+pub trait ModIntBase:
+    Copy
+    + Eq
+    + fmt::Debug
+    + fmt::Display
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    /// 現在のmod
+    fn modulus() -> u32;
+
+    /// `0 <= v < modulus()`であることを仮定して、そのままの値で作る
+    fn raw(v: u32) -> Self;
+
+    /// 中身の値、`[0, modulus())`
+    fn val(self) -> u32;
+
+    /// 任意の`u64`からmodを取って作る
+    fn from_u64(v: u64) -> Self {
+        Self::raw((v % Self::modulus() as u64) as u32)
+    }
+
+    /// 任意の`i64`からmodを取って作る
+    fn from_i64(v: i64) -> Self {
+        let m = Self::modulus() as i64;
+        Self::raw(((v % m + m) % m) as u32)
+    }
+
+    /// 二分累乗法
+    ///
+    /// `O(log exp)`
+    fn pow(self, mut exp: u64) -> Self {
+        let mut res = Self::one();
+        let mut base = self;
+        while exp > 0 {
+            if exp & 1 != 0 {
+                res *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        res
+    }
+
+    /// 乗法逆元
+    ///
+    /// # Panic
+    /// 逆元が存在しないとき
+    fn inv(self) -> Self {
+        let inv = inv_mod(self.val() as i64, Self::modulus() as i64);
+        Self::raw(inv.expect("the inverse does not exist") as u32)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> StaticModInt<M> {
+    /// mod
+    pub fn modulus() -> u32 {
+        M::VALUE
+    }
+
+    /// 整数からmodを取って作る
+    ///
+    /// 符号付き・符号なしの全ての整数型を受け付ける
+    pub fn new<T: Into<Self>>(n: T) -> Self {
+        n.into()
+    }
+
+    /// `0 <= v < M::VALUE`であることを仮定して、そのままの値で作る
+    pub fn raw(v: u32) -> Self {
+        Self {
+            val: v,
+            phantom: PhantomData,
+        }
+    }
+
+    /// 中身の値
+    pub fn val(self) -> u32 {
+        self.val
+    }
+
+    /// 二分累乗法
+    pub fn pow(self, exp: u64) -> Self {
+        <Self as ModIntBase>::pow(self, exp)
+    }
+
+    /// 乗法逆元
+    ///
+    /// modが素数ならフェルマーの小定理、そうでなければ拡張ユークリッドの互除法を使う
+    ///
+    /// # Panic
+    /// 逆元が存在しないとき
+    pub fn inv(self) -> Self {
+        if M::IS_PRIME {
+            assert!(self.val != 0, "the inverse does not exist");
+            self.pow(M::VALUE as u64 - 2)
+        } else {
+            let inv = inv_mod(self.val as i64, M::VALUE as i64);
+            Self::raw(inv.expect("the inverse does not exist") as u32)
+        }
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> ModIntBase for StaticModInt<M> {
+    fn modulus() -> u32 {
+        M::VALUE
+    }
+
+    fn raw(v: u32) -> Self {
+        Self::raw(v)
+    }
+
+    fn val(self) -> u32 {
+        self.val
+    }
+
+    fn inv(self) -> Self {
+        Self::inv(self)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Default for StaticModInt<M> {
+    fn default() -> Self {
+        Self::raw(0)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> fmt::Display for StaticModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Add for StaticModInt<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        let v = self.val as u64 + rhs.val as u64;
+        let m = M::VALUE as u64;
+        Self::raw(if v >= m { v - m } else { v } as u32)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Sub for StaticModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        if self.val >= rhs.val {
+            Self::raw(self.val - rhs.val)
+        } else {
+            Self::raw(M::VALUE - rhs.val + self.val)
+        }
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Mul for StaticModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw((self.val as u64 * rhs.val as u64 % M::VALUE as u64) as u32)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Div for StaticModInt<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Neg for StaticModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+/// 代入演算子と、参照との間の演算を実装する
+#[snippet("static_modint")]
+macro_rules! impl_static_modint_ops {
+    ($(($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident)),*) => {
+        $(
+            impl<M: Modulus> $OpAssign for StaticModInt<M> {
+                fn $op_assign(&mut self, rhs: Self) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+            impl<'a, M: Modulus> $OpAssign<&'a Self> for StaticModInt<M> {
+                fn $op_assign(&mut self, rhs: &'a Self) {
+                    *self = $Op::$op(*self, *rhs);
+                }
+            }
+            impl<'a, M: Modulus> $Op<&'a StaticModInt<M>> for StaticModInt<M> {
+                type Output = Self;
+                fn $op(self, rhs: &'a Self) -> Self {
+                    $Op::$op(self, *rhs)
+                }
+            }
+            impl<'a, M: Modulus> $Op<StaticModInt<M>> for &'a StaticModInt<M> {
+                type Output = StaticModInt<M>;
+                fn $op(self, rhs: StaticModInt<M>) -> StaticModInt<M> {
+                    $Op::$op(*self, rhs)
+                }
+            }
+            impl<'a, 'b, M: Modulus> $Op<&'b StaticModInt<M>> for &'a StaticModInt<M> {
+                type Output = StaticModInt<M>;
+                fn $op(self, rhs: &'b StaticModInt<M>) -> StaticModInt<M> {
+                    $Op::$op(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("static_modint")]
+impl_static_modint_ops!(
+    (Add, add, AddAssign, add_assign),
+    (Sub, sub, SubAssign, sub_assign),
+    (Mul, mul, MulAssign, mul_assign),
+    (Div, div, DivAssign, div_assign)
+);
+
+#[snippet("static_modint")]
+macro_rules! impl_static_modint_from {
+    ($($t:ty),*) => {
+        $(
+            impl<M: Modulus> From<$t> for StaticModInt<M> {
+                fn from(n: $t) -> Self {
+                    Self::from_u64(n as u64)
+                }
+            }
+        )*
+    };
+    (signed $($t:ty),*) => {
+        $(
+            impl<M: Modulus> From<$t> for StaticModInt<M> {
+                fn from(n: $t) -> Self {
+                    Self::from_i64(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("static_modint")]
+impl_static_modint_from!(usize, u8, u16, u32, u64);
+#[snippet("static_modint")]
+impl_static_modint_from!(signed isize, i8, i16, i32, i64);
+
+#[snippet("static_modint")]
+impl<M: Modulus> Zero for StaticModInt<M> {
+    fn zero() -> Self {
+        Self::raw(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.val == 0
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> One for StaticModInt<M> {
+    fn one() -> Self {
+        Self::raw(1 % M::VALUE)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Sum for StaticModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + b)
+    }
+}
+
+#[snippet("static_modint")]
+impl<'a, M: Modulus> Sum<&'a Self> for StaticModInt<M> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |a, b| a + *b)
+    }
+}
+
+#[snippet("static_modint")]
+impl<M: Modulus> Product for StaticModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |a, b| a * b)
+    }
+}
+
+#[snippet("static_modint")]
+impl<'a, M: Modulus> Product<&'a Self> for StaticModInt<M> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |a, b| a * *b)
+    }
+}
+
+#[test]
+fn static_modint_size() {
+    assert_eq!(std::mem::size_of::<ModInt998244353>(), 4);
+    assert_eq!(ModInt998244353::modulus(), 998_244_353);
+    assert_eq!(ModInt1000000007::modulus(), 1_000_000_007);
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn static_modint_ops() {
+    type Mint = ModInt1000000007;
+
+    let a = Mint::new(-1);
+    assert_eq!(a.val(), 1_000_000_006);
+    let b = Mint::from(2u32);
+    assert_eq!((a + b).val(), 1);
+    assert_eq!((b - a).val(), 3);
+    assert_eq!((a * a).val(), 1);
+    let c = Mint::new(2);
+    assert_eq!((b / c).val(), 1);
+    assert_eq!((Mint::new(1) / b * b).val(), 1);
+    assert_eq!((-b).val(), 1_000_000_005);
+    assert_eq!(b.pow(10).val(), 1024);
+    assert_eq!((&a + &b).val(), 1);
+
+    let mut c = Mint::new(5);
+    c += b;
+    c *= &b;
+    c -= Mint::new(4);
+    c /= Mint::new(5);
+    assert_eq!(c.val(), 2);
+
+    assert_eq!(format!("{}", Mint::new(42)), "42");
+
+    assert_eq!(Mint::new(1_000_000_008u64).val(), 1);
+    assert_eq!(Mint::new(3usize).val(), 3);
+    assert_eq!(Mint::new(-3i64).val(), 1_000_000_004);
+}
+
+#[test]
+fn static_modint_identities() {
+    type Mint = ModInt998244353;
+
+    let v = (1..=5).map(Mint::new).collect::<Vec<_>>();
+    assert_eq!(v.iter().sum::<Mint>().val(), 15);
+    assert_eq!(v.iter().cloned().product::<Mint>().val(), 120);
+    assert!(Mint::zero().is_zero());
+    assert_eq!(Mint::one() + Mint::zero(), Mint::one());
+    assert_eq!(
+        Vec::<Mint>::new().into_iter().product::<Mint>(),
+        Mint::one()
+    );
+}
+
+#[test]
+fn static_modint_user_defined() {
+    modulus_def! {
+        Mod12,
+        12,
+        false
+    }
+    type Mint = StaticModInt<Mod12>;
+
+    assert_eq!(Mint::new(5).inv().val(), 5);
+    assert_eq!((Mint::new(7) / Mint::new(5)).val(), 11);
+    assert_eq!(Mint::new(26).val(), 2);
+}
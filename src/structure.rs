@@ -1,8 +1,13 @@
+#[macro_use]
+pub mod algebraic_traits;
+
 pub mod accumulate;
 pub mod delayed_segment_tree;
 pub mod fenwick_tree;
+pub mod fenwick_tree_2d;
 pub mod generic_fenwick_tree;
+pub mod range_fenwick_tree;
 pub mod segment_tree;
+pub mod segment_tree_2d;
+pub mod sparse_table;
 pub mod union_find;
-
-pub mod algebraic_traits;
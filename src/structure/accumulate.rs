@@ -1,7 +1,7 @@
 use cargo_snippet::snippet;
 
-use num::traits::identities::{zero, Zero};
-use std::ops::{Add, Index, Sub};
+use super::algebraic_traits::abel::Abel;
+use std::ops::Index;
 
 /// 累積和型
 ///
@@ -9,13 +9,15 @@ use std::ops::{Add, Index, Sub};
 /// 区間 O(1)
 /// 更新は作り直す以外に出来ない
 ///
-/// 逆操作はimos法,
+/// 逆操作はimos法, `Imos`を参照
+///
+/// `Abel`についてgenericなので、xorや`ModInt`の累積も取れる
 #[snippet("accumulate")]
 #[derive(PartialEq, Clone, Debug)]
 pub struct Accumulate<T>(Vec<T>);
 
 #[snippet("accumulate")]
-impl<T: Zero + Copy + Add<Output = T> + Sub<Output = T>> Accumulate<T> {
+impl<T: Abel + Copy> Accumulate<T> {
     /// `[0, i)`の総和
     pub fn sum(&self, i: usize) -> T {
         self[i]
@@ -23,12 +25,12 @@ impl<T: Zero + Copy + Add<Output = T> + Sub<Output = T>> Accumulate<T> {
 
     /// 累積和を利用して`[i, j)`間の総和を`O(1)`で求める
     pub fn pertical_sum(&self, i: usize, j: usize) -> T {
-        self[j] - self[i]
+        T::inverse(&self[j], &self[i])
     }
 }
 
 #[snippet("accumulate")]
-impl<T: Zero + Add<Output = T> + Copy> Index<usize> for Accumulate<T> {
+impl<T: Abel + Copy> Index<usize> for Accumulate<T> {
     type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
@@ -39,17 +41,219 @@ impl<T: Zero + Add<Output = T> + Copy> Index<usize> for Accumulate<T> {
 /// 累積和をとります
 ///
 /// `O(n)`
-pub fn accumulate<T: Zero + Copy + Add<Output = T> + Sub<Output = T>>(v: &[T]) -> Accumulate<T> {
+pub fn accumulate<T: Abel + Copy>(v: &[T]) -> Accumulate<T> {
     let mut res = Vec::with_capacity(v.len() + 1);
-    let mut i: T = zero();
+    let mut i = T::identity();
     res.push(i);
     for e in v {
-        i = i + *e;
+        i = T::op(&i, e);
         res.push(i);
     }
     Accumulate(res)
 }
 
+/// 二次元累積和型
+///
+/// 初期化 O(hw)
+/// 矩形領域 O(1)
+#[snippet("accumulate")]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Accumulate2D<T>(Vec<Vec<T>>);
+
+#[snippet("accumulate")]
+impl<T: Abel + Copy> Accumulate2D<T> {
+    /// `[0, i) x [0, j)`の総和
+    pub fn sum(&self, i: usize, j: usize) -> T {
+        self.0[i][j]
+    }
+
+    /// `[from.0, to.0) x [from.1, to.1)`の矩形領域の総和を`O(1)`で求める
+    pub fn partial_sum(&self, from: (usize, usize), to: (usize, usize)) -> T {
+        let s = &self.0;
+        let plus = T::op(&s[to.0][to.1], &s[from.0][from.1]);
+        let minus = T::op(&s[from.0][to.1], &s[to.0][from.1]);
+        T::inverse(&plus, &minus)
+    }
+}
+
+#[snippet("accumulate")]
+/// 二次元累積和をとります
+///
+/// `O(hw)`
+pub fn accumulate_2d<T: Abel + Copy>(v: &[Vec<T>]) -> Accumulate2D<T> {
+    let h = v.len();
+    let w = v.first().map_or(0, |r| r.len());
+    let mut res = vec![vec![T::identity(); w + 1]; h + 1];
+    for i in 0..h {
+        for j in 0..w {
+            let plus = T::op(&T::op(&res[i][j + 1], &res[i + 1][j]), &v[i][j]);
+            res[i + 1][j + 1] = T::inverse(&plus, &res[i][j]);
+        }
+    }
+    Accumulate2D(res)
+}
+
+/// 三次元累積和型
+///
+/// 初期化 O(xyz)
+/// 直方体領域 O(1)
+#[snippet("accumulate")]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Accumulate3D<T>(Vec<Vec<Vec<T>>>);
+
+#[snippet("accumulate")]
+impl<T: Abel + Copy> Accumulate3D<T> {
+    /// `[0, i) x [0, j) x [0, k)`の総和
+    pub fn sum(&self, i: usize, j: usize, k: usize) -> T {
+        self.0[i][j][k]
+    }
+
+    /// `[from.0, to.0) x [from.1, to.1) x [from.2, to.2)`の直方体領域の総和を`O(1)`で求める
+    pub fn partial_sum(&self, from: (usize, usize, usize), to: (usize, usize, usize)) -> T {
+        let s = &self.0;
+        let (x1, y1, z1) = from;
+        let (x2, y2, z2) = to;
+        let plus = T::op(
+            &T::op(&s[x2][y2][z2], &s[x1][y1][z2]),
+            &T::op(&s[x1][y2][z1], &s[x2][y1][z1]),
+        );
+        let minus = T::op(
+            &T::op(&s[x1][y2][z2], &s[x2][y1][z2]),
+            &T::op(&s[x2][y2][z1], &s[x1][y1][z1]),
+        );
+        T::inverse(&plus, &minus)
+    }
+}
+
+#[snippet("accumulate")]
+/// 三次元累積和をとります
+///
+/// `O(xyz)`
+pub fn accumulate_3d<T: Abel + Copy>(v: &[Vec<Vec<T>>]) -> Accumulate3D<T> {
+    let nx = v.len();
+    let ny = v.first().map_or(0, |r| r.len());
+    let nz = v.first().and_then(|r| r.first()).map_or(0, |r| r.len());
+    let mut s = vec![vec![vec![T::identity(); nz + 1]; ny + 1]; nx + 1];
+    for i in 0..nx {
+        for j in 0..ny {
+            for k in 0..nz {
+                let plus = T::op(
+                    &T::op(&v[i][j][k], &s[i][j][k]),
+                    &T::op(
+                        &s[i][j + 1][k + 1],
+                        &T::op(&s[i + 1][j][k + 1], &s[i + 1][j + 1][k]),
+                    ),
+                );
+                let minus = T::op(&T::op(&s[i][j][k + 1], &s[i][j + 1][k]), &s[i + 1][j][k]);
+                s[i + 1][j + 1][k + 1] = T::inverse(&plus, &minus);
+            }
+        }
+    }
+    Accumulate3D(s)
+}
+
+/// imos法
+///
+/// 区間への加算を`O(1)`で積んでおき、
+/// `build()`で`O(n)`かけて各要素の値を復元する
+#[snippet("accumulate")]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Imos<T>(Vec<T>);
+
+#[snippet("accumulate")]
+impl<T: Abel + Copy> Imos<T> {
+    /// 長さ`n`で単位元で初期化
+    pub fn new(n: usize) -> Self {
+        Imos(vec![T::identity(); n + 1])
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `[from, to)`の各要素に`v`を加える
+    ///
+    /// `O(1)`
+    pub fn add(&mut self, from: usize, to: usize, v: T) {
+        self.0[from] = T::op(&self.0[from], &v);
+        self.0[to] = T::inverse(&self.0[to], &v);
+    }
+
+    /// 各要素の値を復元する
+    ///
+    /// `O(n)`
+    pub fn build(&self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len());
+        let mut cur = T::identity();
+        for e in &self.0[..self.len()] {
+            cur = T::op(&cur, e);
+            res.push(cur);
+        }
+        res
+    }
+}
+
+/// 二次元のimos法
+///
+/// 矩形領域への加算を`O(1)`で積んでおき、
+/// `build()`で`O(hw)`かけて各要素の値を復元する
+#[snippet("accumulate")]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Imos2D<T>(Vec<Vec<T>>);
+
+#[snippet("accumulate")]
+impl<T: Abel + Copy> Imos2D<T> {
+    /// サイズ`h x w`で単位元で初期化
+    pub fn new(h: usize, w: usize) -> Self {
+        Imos2D(vec![vec![T::identity(); w + 1]; h + 1])
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.0[0].len() - 1
+    }
+
+    /// `[from.0, to.0) x [from.1, to.1)`の各要素に`v`を加える
+    ///
+    /// `O(1)`
+    pub fn add(&mut self, from: (usize, usize), to: (usize, usize), v: T) {
+        let d = &mut self.0;
+        d[from.0][from.1] = T::op(&d[from.0][from.1], &v);
+        d[from.0][to.1] = T::inverse(&d[from.0][to.1], &v);
+        d[to.0][from.1] = T::inverse(&d[to.0][from.1], &v);
+        d[to.0][to.1] = T::op(&d[to.0][to.1], &v);
+    }
+
+    /// 各要素の値を復元する
+    ///
+    /// `O(hw)`
+    pub fn build(&self) -> Vec<Vec<T>> {
+        let (h, w) = (self.height(), self.width());
+        let mut res = self.0[..h]
+            .iter()
+            .map(|r| r[..w].to_vec())
+            .collect::<Vec<_>>();
+        for r in res.iter_mut() {
+            for j in 1..w {
+                r[j] = T::op(&r[j - 1], &r[j]);
+            }
+        }
+        for i in 1..h {
+            for j in 0..w {
+                res[i][j] = T::op(&res[i - 1][j], &res[i][j]);
+            }
+        }
+        res
+    }
+}
+
 #[test]
 fn acum_test() {
     let v = vec![1, 2, 3, 4, 5];
@@ -64,3 +268,83 @@ fn sum_test() {
     assert_eq!(acm.pertical_sum(0, 3), 6);
     assert_eq!(acm.pertical_sum(4, 5), 5);
 }
+
+#[test]
+fn xor_accumulate_test() {
+    use super::algebraic_traits::monoid::Monoid;
+
+    abel_def! {
+        Xor<u32>,
+        0,
+        |x, y| x ^ y,
+        |x, y| x ^ y
+    }
+
+    let v = [3, 5, 6, 1].iter().map(|&x| Xor(x)).collect::<Vec<_>>();
+    let acm = accumulate(&v);
+    assert_eq!(acm.pertical_sum(1, 3).0, 3);
+    assert_eq!(acm.pertical_sum(0, 4).0, 1);
+}
+
+#[test]
+fn accumulate_2d_test() {
+    let v = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+    let acm = accumulate_2d(&v);
+    assert_eq!(acm.sum(2, 2), 12);
+    assert_eq!(acm.partial_sum((1, 1), (3, 3)), 28);
+    assert_eq!(acm.partial_sum((0, 2), (3, 3)), 18);
+    assert_eq!(acm.partial_sum((1, 0), (1, 3)), 0);
+}
+
+#[test]
+fn accumulate_3d_test() {
+    let v = (0..3)
+        .map(|i| {
+            (0..4)
+                .map(|j| (0..2).map(|k| i * 100 + j * 10 + k).collect())
+                .collect()
+        })
+        .collect::<Vec<Vec<Vec<i64>>>>();
+    let acm = accumulate_3d(&v);
+
+    let brute = |from: (usize, usize, usize), to: (usize, usize, usize)| {
+        let mut s = 0;
+        for a in v.iter().take(to.0).skip(from.0) {
+            for b in a.iter().take(to.1).skip(from.1) {
+                for c in b.iter().take(to.2).skip(from.2) {
+                    s += c;
+                }
+            }
+        }
+        s
+    };
+
+    assert_eq!(acm.sum(3, 4, 2), brute((0, 0, 0), (3, 4, 2)));
+    assert_eq!(
+        acm.partial_sum((1, 1, 1), (3, 3, 2)),
+        brute((1, 1, 1), (3, 3, 2))
+    );
+    assert_eq!(
+        acm.partial_sum((0, 2, 0), (2, 4, 1)),
+        brute((0, 2, 0), (2, 4, 1))
+    );
+}
+
+#[test]
+fn imos_test() {
+    let mut imos = Imos::new(5);
+    imos.add(0, 3, 1);
+    imos.add(2, 5, 2);
+    imos.add(4, 5, -1);
+    assert_eq!(imos.build(), vec![1, 1, 3, 2, 1]);
+    assert_eq!(imos.len(), 5);
+    assert!(Imos::<i64>::new(0).is_empty());
+
+    let mut imos = Imos2D::new(3, 4);
+    imos.add((0, 0), (2, 2), 1);
+    imos.add((1, 1), (3, 4), 2);
+    assert_eq!(
+        imos.build(),
+        vec![vec![1, 1, 0, 0], vec![1, 3, 2, 2], vec![0, 2, 2, 2]]
+    );
+}
@@ -2,3 +2,4 @@
 pub mod monoid;
 #[macro_use]
 pub mod abel;
+pub mod idempotent;
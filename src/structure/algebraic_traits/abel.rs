@@ -1,6 +1,8 @@
 use cargo_snippet::snippet;
 
 use super::monoid::Monoid;
+use num_traits::Zero;
+use std::ops::{Add, Sub};
 
 /// 単位元が定義される `T -> T -> T`型の演算
 ///
@@ -11,6 +13,17 @@ pub trait Abel: Sized + Monoid {
     fn inverse(x: &Self, y: &Self) -> Self;
 }
 
+/// `Zero`と`Add`、`Sub`を持つ型(プリミティブな数値型や`ModInt`)は
+/// 加法についてのAbelになる
+///
+/// 符号なし整数では逆元を取る途中で負になるとoverflowするので注意
+#[snippet("abel")]
+impl<T: Zero + Clone + PartialEq + Add<Output = T> + Sub<Output = T>> Abel for T {
+    fn inverse(x: &Self, y: &Self) -> Self {
+        x.clone() - y.clone()
+    }
+}
+
 /// Abelトレイトの自動実装マクロ
 ///
 /// 一行目にアーベル名、
@@ -80,3 +93,11 @@ fn able_test() {
     assert_eq!(Add::op(&a, &b).0, 8);
     assert_eq!(Add::inverse(&a, &b).0, 2);
 }
+
+#[test]
+fn primitive_abel_test() {
+    assert_eq!(i64::identity(), 0);
+    assert_eq!(i64::op(&5, &-3), 2);
+    assert_eq!(i64::inverse(&5, &-3), 8);
+    assert!((f64::inverse(&1.5, &0.5) - 1.0).abs() < 1e-9);
+}
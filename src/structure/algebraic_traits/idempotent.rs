@@ -0,0 +1,9 @@
+use cargo_snippet::snippet;
+
+use super::monoid::Monoid;
+
+/// `op(x, x) == x` を満たすMonoid
+///
+/// min, max, gcd など、区間が重なってもfoldの結果が変わらない演算
+#[snippet("idempotent")]
+pub trait Idempotent: Monoid {}
@@ -1,4 +1,6 @@
 use cargo_snippet::snippet;
+use num_traits::Zero;
+use std::ops::Add;
 
 /// 単位元が定義される `T -> T -> T`型の演算
 #[snippet("monoid")]
@@ -12,6 +14,19 @@ pub trait Monoid: Sized + PartialEq {
     }
 }
 
+/// `Zero`と`Add`を持つ型(プリミティブな数値型や`ModInt`)は
+/// 加法についてのMonoidになる
+#[snippet("monoid")]
+impl<T: Zero + Clone + PartialEq + Add<Output = T>> Monoid for T {
+    fn identity() -> Self {
+        T::zero()
+    }
+
+    fn op(x: &Self, y: &Self) -> Self {
+        x.clone() + y.clone()
+    }
+}
+
 /// Monoidトレイトの自動実装マクロ
 ///
 /// 一行目にモノイド名、
@@ -1,34 +1,35 @@
-use cargo_snippet::snippet;
-use num::Zero;
-use std::ops::{Add, Sub};
+use super::algebraic_traits::abel::Abel;
 
-// 逆操作が定まる(体?)であればgenericな定義が出来そう?
+use cargo_snippet::snippet;
 
 /// 更新、区間和の取得がともに`O(log n)`で
 /// 可能なデータ構造
+///
+/// `Abel`についてgenericで、
+/// プリミティブな数値型や`ModInt`はそのまま(加法として)載せられる
 #[snippet("fenwick_tree")]
 #[derive(Debug, Clone)]
-pub struct FenwickTree<T: Add> {
+pub struct FenwickTree<T: Abel> {
     len: usize,
     segment: Vec<T>,
 }
 
 #[snippet("fenwick_tree")]
-impl<T: Add + Sub<Output = T> + Zero + Clone + Ord + Into<usize>> FenwickTree<T> {
-    /// サイズを指定して、zerosで初期化
+impl<T: Abel + Clone> FenwickTree<T> {
+    /// サイズを指定して、単位元で初期化
     pub fn new(n: usize) -> Self {
         Self {
             len: n,
-            segment: vec![T::zero(); n + 1],
+            segment: vec![T::identity(); n + 1],
         }
     }
 
     /// 配列から構築
-    pub fn from(v: &[T]) -> Self {
-        let n = v.len();
+    pub fn from<I: Into<T> + Copy>(a: &[I]) -> Self {
+        let n = a.len();
         let mut f = Self::new(n);
-        for (i, v) in v.iter().enumerate() {
-            f.add(i, v.clone());
+        for (i, &v) in a.iter().enumerate() {
+            f.merge(i, v.into());
         }
         f
     }
@@ -38,42 +39,107 @@ impl<T: Add + Sub<Output = T> + Zero + Clone + Ord + Into<usize>> FenwickTree<T>
         self.len
     }
 
-    /// # 更新
-    /// `i`番目の要素 += `v`
+    /// クエリ: 要素`i` に `v` をマージする
     ///
     /// `O(log n)`
-    pub fn add(&mut self, i: usize, v: T) {
+    pub fn merge(&mut self, i: usize, v: T) {
         let mut i = i + 1;
         while i <= self.len() {
-            self.segment[i] = self.segment[i].clone() + v.clone();
+            self.segment[i] = T::op(&self.segment[i], &v);
             i += (i as i64 & -(i as i64)) as usize;
         }
     }
 
+    /// クエリ: 要素`i` を `v` に変更する
+    ///
+    /// 現在の値を取り除いてから`v`をマージする
+    /// (符号なし整数でも差分が負にならない)
+    pub fn set(&mut self, i: usize, v: T) {
+        let old = self.get(i);
+        let mut j = i + 1;
+        while j <= self.len() {
+            self.segment[j] = T::inverse(&self.segment[j], &old);
+            j += (j as i64 & -(j as i64)) as usize;
+        }
+        self.merge(i, v);
+    }
+
+    /// `set`と同じ
+    pub fn update(&mut self, i: usize, v: T) {
+        self.set(i, v);
+    }
+
+    /// 要素`i`の値
+    pub fn get(&self, i: usize) -> T {
+        self.range(i, i + 1)
+    }
+
     /// `[0..i)`の区間和
     ///
     /// `O(log n)`
-    pub fn sum(&self, mut i: usize) -> T {
-        let mut s = T::zero();
+    pub fn accumulate(&self, mut i: usize) -> T {
+        let mut s = T::identity();
         while i > 0 {
-            s = s.clone() + self.segment[i].clone();
+            s = T::op(&s, &self.segment[i]);
             i -= (i as i64 & -(i as i64)) as usize;
         }
         s
     }
 
-    /// `[a..b)`の区間和
+    /// `[from..to)`の区間和
     ///
     /// `O(log n)`
+    pub fn range(&self, from: usize, to: usize) -> T {
+        T::inverse(&self.accumulate(to), &self.accumulate(from))
+    }
+
+    /// `merge`と同じ
+    pub fn add(&mut self, i: usize, v: T) {
+        self.merge(i, v);
+    }
+
+    /// `accumulate`と同じ
+    pub fn sum(&self, i: usize) -> T {
+        self.accumulate(i)
+    }
+
+    /// `range`と同じ
     pub fn partial_sum(&self, from: usize, to: usize) -> T {
-        self.sum(to) - self.sum(from)
+        self.range(from, to)
+    }
+}
+
+#[snippet("fenwick_tree")]
+impl<T: Abel + Clone + PartialOrd> FenwickTree<T> {
+    /// `accumulate(i + 1) >= w` となる最小の`i`
+    ///
+    /// 存在しなければ`len()`を返す
+    ///
+    /// 全ての要素が単位元以上(累積が単調増加)であることを仮定する
+    ///
+    /// `O(log n)`
+    pub fn lower_bound(&self, w: T) -> usize {
+        let mut pos = 0;
+        let mut acc = T::identity();
+        let mut step = self.len().next_power_of_two();
+        while step > 0 {
+            if pos + step <= self.len() {
+                let next = T::op(&acc, &self.segment[pos + step]);
+                if next < w {
+                    pos += step;
+                    acc = next;
+                }
+            }
+            step >>= 1;
+        }
+        pos
     }
 }
 
 #[test]
 fn fenwick_test() {
     let v = vec![1usize, 2, 3, 4];
-    let mut f = FenwickTree::from(&v);
+    let mut f = FenwickTree::<usize>::from(&v);
 
     assert_eq!(f.sum(3), 6);
     assert_eq!(f.partial_sum(1, 3), 5);
@@ -82,3 +148,42 @@ fn fenwick_test() {
 
     assert_eq!(f.sum(2), 103);
 }
+
+#[test]
+fn fenwick_unsigned_set_test() {
+    let mut f = FenwickTree::<u64>::from(&[5u64, 7, 1]);
+    f.set(1, 2);
+    f.set(0, 0);
+
+    assert_eq!(f.get(1), 2);
+    assert_eq!(f.accumulate(3), 3);
+    assert_eq!(f.range(1, 3), 3);
+}
+
+#[test]
+fn fenwick_i64_test() {
+    let mut f = FenwickTree::<i64>::new(4);
+    f.merge(0, -3);
+    f.merge(2, 5);
+    f.set(3, -1);
+
+    assert_eq!(f.accumulate(4), 1);
+    assert_eq!(f.range(1, 4), 4);
+    assert_eq!(f.get(2), 5);
+}
+
+#[test]
+fn fenwick_modint_test() {
+    use crate::modint::ModInt;
+
+    let v = (1..=4).map(|x| ModInt::new(x, 7)).collect::<Vec<_>>();
+    let mut f = FenwickTree::<ModInt>::from(&v);
+
+    assert_eq!(f.accumulate(4).get(), 3);
+    assert_eq!(f.range(1, 3).get(), 5);
+
+    f.set(0, ModInt::new(6, 7));
+
+    assert_eq!(f.accumulate(4).get(), 1);
+    assert_eq!(f.get(1).get(), 2);
+}
@@ -0,0 +1,258 @@
+use super::algebraic_traits::abel::Abel;
+
+use cargo_snippet::snippet;
+
+/// 二次元のFenwickTree
+///
+/// 一点への`merge`、矩形領域の和の取得がともに`O(log h log w)`
+#[snippet("fenwick_tree_2d")]
+#[derive(Debug, Clone)]
+pub struct FenwickTree2D<T: Abel> {
+    height: usize,
+    width: usize,
+    segment: Vec<Vec<T>>,
+}
+
+#[snippet("fenwick_tree_2d")]
+impl<T: Abel + Clone> FenwickTree2D<T> {
+    /// サイズ`h x w`を指定して、単位元で初期化
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            height: h,
+            width: w,
+            segment: vec![vec![T::identity(); w + 1]; h + 1],
+        }
+    }
+
+    /// 二次元配列から構築
+    ///
+    /// `O(hw log h log w)`
+    pub fn from<I: Into<T> + Copy>(a: &[Vec<I>]) -> Self {
+        let h = a.len();
+        let w = a.first().map_or(0, |r| r.len());
+        let mut f = Self::new(h, w);
+        for (i, r) in a.iter().enumerate() {
+            for (j, &v) in r.iter().enumerate() {
+                f.merge(i, j, v.into());
+            }
+        }
+        f
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// クエリ: 要素`(i, j)` に `v` をマージする
+    ///
+    /// `O(log h log w)`
+    pub fn merge(&mut self, i: usize, j: usize, v: T) {
+        let mut i = i + 1;
+        while i <= self.height() {
+            let mut j = j + 1;
+            while j <= self.width() {
+                self.segment[i][j] = T::op(&self.segment[i][j], &v);
+                j += (j as i64 & -(j as i64)) as usize;
+            }
+            i += (i as i64 & -(i as i64)) as usize;
+        }
+    }
+
+    /// `[0..i) x [0..j)`の矩形領域の和
+    ///
+    /// `O(log h log w)`
+    pub fn accumulate(&self, mut i: usize, j: usize) -> T {
+        let mut s = T::identity();
+        while i > 0 {
+            let mut j = j;
+            while j > 0 {
+                s = T::op(&s, &self.segment[i][j]);
+                j -= (j as i64 & -(j as i64)) as usize;
+            }
+            i -= (i as i64 & -(i as i64)) as usize;
+        }
+        s
+    }
+
+    /// `[from.0..to.0) x [from.1..to.1)`の矩形領域の和
+    ///
+    /// `O(log h log w)`
+    pub fn range(&self, from: (usize, usize), to: (usize, usize)) -> T {
+        let whole = T::op(
+            &self.accumulate(to.0, to.1),
+            &self.accumulate(from.0, from.1),
+        );
+        let sides = T::op(
+            &self.accumulate(from.0, to.1),
+            &self.accumulate(to.0, from.1),
+        );
+        T::inverse(&whole, &sides)
+    }
+}
+
+/// `v`の中で`x`以上の要素が最初に現れるindex
+#[snippet("fenwick_tree_2d")]
+fn lower_bound_idx<C: Ord>(v: &[C], x: &C) -> usize {
+    v.binary_search_by(|p| p.cmp(x).then(std::cmp::Ordering::Greater))
+        .unwrap_err()
+}
+
+/// 座標圧縮を用いたオフラインの二次元FenwickTree
+///
+/// 更新する可能性のある点を全て先に与えて構築する。
+/// 座標は`10^9`を超えるような疎な点の集合でもよい
+///
+/// 構築 `O(n log n)`
+/// 一点への`merge`、矩形領域の和の取得がともに`O(log^2 n)`
+#[snippet("fenwick_tree_2d")]
+#[derive(Debug, Clone)]
+pub struct CompressedFenwickTree2D<C: Ord, T: Abel> {
+    /// 圧縮後の一次元目の座標
+    xs: Vec<C>,
+    /// `ys[i]` => Fenwickの`i`番目のノードが担当する点の二次元目の座標
+    ys: Vec<Vec<C>>,
+    segment: Vec<Vec<T>>,
+}
+
+#[snippet("fenwick_tree_2d")]
+impl<C: Ord + Copy, T: Abel + Clone> CompressedFenwickTree2D<C, T> {
+    /// 更新する可能性のある点を全て与えて、単位元で初期化
+    pub fn new(points: &[(C, C)]) -> Self {
+        let mut xs = points.iter().map(|p| p.0).collect::<Vec<_>>();
+        xs.sort();
+        xs.dedup();
+
+        let n = xs.len();
+        let mut ys = vec![Vec::new(); n + 1];
+        for &(x, y) in points {
+            let mut i = lower_bound_idx(&xs, &x) + 1;
+            while i <= n {
+                ys[i].push(y);
+                i += (i as i64 & -(i as i64)) as usize;
+            }
+        }
+        for y in ys.iter_mut() {
+            y.sort();
+            y.dedup();
+        }
+
+        let segment = ys
+            .iter()
+            .map(|y| vec![T::identity(); y.len() + 1])
+            .collect();
+
+        Self { xs, ys, segment }
+    }
+
+    /// クエリ: 点`(x, y)` に `v` をマージする
+    ///
+    /// # Panic
+    /// `(x, y)`が構築時に与えられていないとき
+    pub fn merge(&mut self, x: C, y: C, v: T) {
+        let mut xi = lower_bound_idx(&self.xs, &x);
+        assert!(xi < self.xs.len() && self.xs[xi] == x);
+        xi += 1;
+        while xi < self.ys.len() {
+            let ys = &self.ys[xi];
+            let mut yi = lower_bound_idx(ys, &y);
+            assert!(yi < ys.len() && ys[yi] == y);
+            yi += 1;
+            while yi <= ys.len() {
+                self.segment[xi][yi] = T::op(&self.segment[xi][yi], &v);
+                yi += (yi as i64 & -(yi as i64)) as usize;
+            }
+            xi += (xi as i64 & -(xi as i64)) as usize;
+        }
+    }
+
+    /// `x' < x && y' < y`を満たす点`(x', y')`の総和
+    pub fn accumulate(&self, x: C, y: C) -> T {
+        let mut s = T::identity();
+        let mut xi = lower_bound_idx(&self.xs, &x);
+        while xi > 0 {
+            let mut yi = lower_bound_idx(&self.ys[xi], &y);
+            while yi > 0 {
+                s = T::op(&s, &self.segment[xi][yi]);
+                yi -= (yi as i64 & -(yi as i64)) as usize;
+            }
+            xi -= (xi as i64 & -(xi as i64)) as usize;
+        }
+        s
+    }
+
+    /// `[from.0..to.0) x [from.1..to.1)`の矩形領域に含まれる点の総和
+    pub fn range(&self, from: (C, C), to: (C, C)) -> T {
+        let whole = T::op(
+            &self.accumulate(to.0, to.1),
+            &self.accumulate(from.0, from.1),
+        );
+        let sides = T::op(
+            &self.accumulate(from.0, to.1),
+            &self.accumulate(to.0, from.1),
+        );
+        T::inverse(&whole, &sides)
+    }
+}
+
+#[test]
+fn fenwick_2d_test() {
+    use super::algebraic_traits::monoid::Monoid;
+
+    abel_def! {
+        Sum<i64>,
+        0,
+        |x, y| x + y,
+        |x, y| x - y
+    }
+
+    let v = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+    let mut ft = FenwickTree2D::<Sum>::from(&v);
+
+    assert_eq!(ft.accumulate(2, 2).0, 12);
+    assert_eq!(ft.accumulate(3, 3).0, 45);
+    assert_eq!(ft.range((1, 1), (3, 3)).0, 28);
+    assert_eq!(ft.range((0, 1), (2, 2)).0, 7);
+    assert_eq!(ft.range((1, 1), (1, 3)).0, 0);
+
+    ft.merge(1, 1, Sum(-5));
+
+    assert_eq!(ft.range((1, 1), (2, 2)).0, 0);
+    assert_eq!(ft.accumulate(3, 3).0, 40);
+}
+
+#[test]
+fn compressed_fenwick_2d_test() {
+    use super::algebraic_traits::monoid::Monoid;
+
+    abel_def! {
+        Sum<i64>,
+        0,
+        |x, y| x + y,
+        |x, y| x - y
+    }
+
+    let points = vec![
+        (1_000_000_000i64, 3i64),
+        (-5, 1_000_000_000),
+        (7, 7),
+        (7, -2),
+    ];
+    let mut ft = CompressedFenwickTree2D::<i64, Sum>::new(&points);
+    for (i, &(x, y)) in points.iter().enumerate() {
+        ft.merge(x, y, Sum(i as i64 + 1));
+    }
+
+    assert_eq!(ft.range((-10, -10), (1_000_000_001, 1_000_000_001)).0, 10);
+    assert_eq!(ft.range((0, -10), (8, 8)).0, 7);
+    assert_eq!(ft.range((7, 0), (8, 8)).0, 3);
+    assert_eq!(ft.accumulate(7, 1_000_000_001).0, 2);
+    assert_eq!(ft.accumulate(1_000_000_000, 4).0, 4);
+
+    ft.merge(7, 7, Sum(10));
+
+    assert_eq!(ft.range((7, 7), (8, 8)).0, 13);
+}
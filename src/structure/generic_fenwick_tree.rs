@@ -1,59 +1,11 @@
-use super::algebraic_traits::abel::Abel;
-
-#[derive(Debug, Clone)]
-pub struct FenwickTree<T: Abel> {
-    len: usize,
-    segment: Vec<T>,
-}
-
-impl<T: Abel + Clone> FenwickTree<T> {
-    pub fn new(n: usize) -> Self {
-        Self {
-            len: n,
-            segment: vec![T::identity(); n + 1],
-        }
-    }
-
-    pub fn from<I: Into<T> + Copy>(a: &[I]) -> Self {
-        let n = a.len();
-        let mut f = Self::new(n);
-        for (i, &v) in a.iter().enumerate() {
-            f.merge(i, v.into());
-        }
-        f
-    }
+//! `FenwickTree`は`Abel`についてgenericな実装に一本化した
+//!
+//! 互換性のために`fenwick_tree::FenwickTree`をそのまま公開する
 
-    pub fn len(&self) -> usize {
-        self.len
-    }
-
-    /// クエリ: 要素`i` に `v` をマージする
-    pub fn merge(&mut self, i: usize, v: T) {
-        let mut i = i + 1;
-        while i <= self.len() {
-            self.segment[i] = T::op(&self.segment[i].clone(), &v.clone());
-            i += (i as i64 & -(i as i64)) as usize;
-        }
-    }
-
-    pub fn update(&mut self, i: usize, v: T) {
-        let inv = T::inverse(&v, &self.segment[i].clone());
-        self.merge(i, inv);
-    }
-
-    pub fn accumulate(&self, mut i: usize) -> T {
-        let mut s = T::identity();
-        while i > 0 {
-            s = T::op(&s.clone(), &self.segment[i].clone());
-            i -= (i as i64 & -(i as i64)) as usize;
-        }
-        s
-    }
+pub use super::fenwick_tree::FenwickTree;
 
-    pub fn range(&self, from: usize, to: usize) -> T {
-        T::inverse(&self.accumulate(to), &self.accumulate(from))
-    }
-}
+#[cfg(test)]
+use super::algebraic_traits::abel::Abel;
 
 #[test]
 fn generic_fenwick() {
@@ -80,3 +32,39 @@ fn generic_fenwick() {
 
     assert_eq!(ft.range(0, 4).0, 7);
 }
+
+#[test]
+fn generic_fenwick_set_and_lower_bound() {
+    use super::algebraic_traits::monoid::Monoid;
+
+    abel_def! {
+        Sum<i64>,
+        0,
+        |x, y| x + y,
+        |x, y| x - y
+    }
+
+    // lower_boundには順序が要る
+    impl PartialOrd for Sum {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    let mut ft = FenwickTree::<Sum>::new(5);
+    ft.set(1, Sum(3));
+    ft.set(3, Sum(2));
+    ft.set(4, Sum(1));
+    ft.set(1, Sum(2));
+
+    assert_eq!(ft.get(1).0, 2);
+    assert_eq!(ft.get(2).0, 0);
+    assert_eq!(ft.range(0, 5).0, 5);
+
+    assert_eq!(ft.lower_bound(Sum(0)), 0);
+    assert_eq!(ft.lower_bound(Sum(1)), 1);
+    assert_eq!(ft.lower_bound(Sum(2)), 1);
+    assert_eq!(ft.lower_bound(Sum(3)), 3);
+    assert_eq!(ft.lower_bound(Sum(5)), 4);
+    assert_eq!(ft.lower_bound(Sum(6)), 5);
+}
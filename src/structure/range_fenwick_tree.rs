@@ -0,0 +1,116 @@
+use super::algebraic_traits::abel::Abel;
+use super::generic_fenwick_tree::FenwickTree;
+
+use cargo_snippet::snippet;
+
+/// `x`を`n`回マージしたもの
+///
+/// 二分累乗法で`O(log n)`
+#[snippet("range_fenwick_tree")]
+fn times<T: Abel + Clone>(x: &T, mut n: usize) -> T {
+    let mut res = T::identity();
+    let mut base = x.clone();
+    while n > 0 {
+        if n & 1 != 0 {
+            res = T::op(&res, &base);
+        }
+        base = T::op(&base, &base);
+        n >>= 1;
+    }
+    res
+}
+
+/// 区間への加算、区間和の取得がともに`O(log n)`で
+/// 可能なFenwickTree
+///
+/// 二本のFenwickTreeを使い、
+/// `[0..i)`の和を`i * b1.accumulate(i) - b2.accumulate(i)`として求める
+#[snippet("range_fenwick_tree")]
+#[derive(Debug, Clone)]
+pub struct RangeFenwickTree<T: Abel> {
+    b1: FenwickTree<T>,
+    b2: FenwickTree<T>,
+}
+
+#[snippet("range_fenwick_tree")]
+impl<T: Abel + Clone> RangeFenwickTree<T> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            b1: FenwickTree::new(n + 1),
+            b2: FenwickTree::new(n + 1),
+        }
+    }
+
+    pub fn from<I: Into<T> + Copy>(a: &[I]) -> Self {
+        let mut f = Self::new(a.len());
+        for (i, &v) in a.iter().enumerate() {
+            f.merge_range(i, i + 1, v.into());
+        }
+        f
+    }
+
+    pub fn len(&self) -> usize {
+        self.b1.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// クエリ: 区間`[from..to)`の全ての要素に `v` をマージする
+    ///
+    /// `O(log n)`
+    pub fn merge_range(&mut self, from: usize, to: usize, v: T) {
+        let neg = T::inverse(&T::identity(), &v);
+        self.b1.merge(from, v.clone());
+        self.b1.merge(to, neg.clone());
+        self.b2.merge(from, times(&v, from));
+        self.b2.merge(to, times(&neg, to));
+    }
+
+    /// `[0..i)`の区間和
+    ///
+    /// `O(log n)`
+    pub fn accumulate(&self, i: usize) -> T {
+        T::inverse(&times(&self.b1.accumulate(i), i), &self.b2.accumulate(i))
+    }
+
+    /// `[from..to)`の区間和
+    ///
+    /// `O(log n)`
+    pub fn range(&self, from: usize, to: usize) -> T {
+        T::inverse(&self.accumulate(to), &self.accumulate(from))
+    }
+}
+
+#[test]
+fn range_fenwick_test() {
+    use super::algebraic_traits::monoid::Monoid;
+
+    abel_def! {
+        Sum<i64>,
+        0,
+        |x, y| x + y,
+        |x, y| x - y
+    }
+
+    let mut ft = RangeFenwickTree::<Sum>::from(&[1i64, 2, 3, 4, 5]);
+
+    assert_eq!(ft.len(), 5);
+    assert!(!ft.is_empty());
+    assert!(RangeFenwickTree::<Sum>::new(0).is_empty());
+    assert_eq!(ft.range(0, 5).0, 15);
+    assert_eq!(ft.range(1, 3).0, 5);
+
+    ft.merge_range(1, 4, Sum(10));
+
+    assert_eq!(ft.range(0, 5).0, 45);
+    assert_eq!(ft.range(0, 1).0, 1);
+    assert_eq!(ft.range(3, 4).0, 14);
+    assert_eq!(ft.range(2, 5).0, 32);
+
+    ft.merge_range(0, 5, Sum(-1));
+
+    assert_eq!(ft.accumulate(2).0, 11);
+    assert_eq!(ft.range(4, 5).0, 4);
+}
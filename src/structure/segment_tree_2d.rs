@@ -0,0 +1,136 @@
+use super::algebraic_traits::monoid::Monoid;
+
+use cargo_snippet::snippet;
+
+/// 二次元の静的なSegmentTree
+///
+/// 構築 `O(hw)`
+/// 矩形領域のfold `O(log h log w)`
+///
+/// 行方向と列方向のfoldの順序が入れ替わるので、
+/// 演算は可換である必要がある
+#[snippet("segment_tree_2d")]
+#[derive(Debug, Clone)]
+pub struct SegmentTree2D<T: Monoid> {
+    /// 一次元目の葉の数
+    height: usize,
+    /// 二次元目の葉の数
+    width: usize,
+    segment: Vec<T>,
+}
+
+#[snippet("segment_tree_2d")]
+impl<T: Monoid + Clone + Copy> SegmentTree2D<T> {
+    pub fn new<I: Into<T> + Copy>(v: &[Vec<I>]) -> Self {
+        let h = v.len();
+        let w = v.first().map_or(0, |r| r.len());
+        let height = h.next_power_of_two();
+        let width = w.next_power_of_two();
+        let mut segment = vec![T::identity(); 4 * height * width];
+        let idx = |i: usize, j: usize| i * 2 * width + j;
+
+        for (i, r) in v.iter().enumerate() {
+            for (j, &e) in r.iter().enumerate() {
+                segment[idx(height + i, width + j)] = e.into();
+            }
+        }
+        for i in height..2 * height {
+            for j in (1..width).rev() {
+                segment[idx(i, j)] = T::op(&segment[idx(i, 2 * j)], &segment[idx(i, 2 * j + 1)]);
+            }
+        }
+        for i in (1..height).rev() {
+            for j in 1..2 * width {
+                segment[idx(i, j)] = T::op(&segment[idx(2 * i, j)], &segment[idx(2 * i + 1, j)]);
+            }
+        }
+
+        Self {
+            height,
+            width,
+            segment,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// `(i, j)`番目の葉の参照をとる
+    pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+        if i >= self.height() || j >= self.width() {
+            None
+        } else {
+            self.segment
+                .get((self.height() + i) * 2 * self.width() + self.width() + j)
+        }
+    }
+
+    /// 行`i`の中で、列`[from..to)`をfoldする
+    fn range_row(&self, i: usize, mut from: usize, mut to: usize) -> T {
+        let row = &self.segment[i * 2 * self.width()..(i + 1) * 2 * self.width()];
+        let mut res = T::identity();
+        from += self.width();
+        to += self.width();
+        while from < to {
+            if from & 1 == 1 {
+                res = T::op(&res, &row[from]);
+                from += 1;
+            }
+            if to & 1 == 1 {
+                to -= 1;
+                res = T::op(&res, &row[to]);
+            }
+            from >>= 1;
+            to >>= 1;
+        }
+        res
+    }
+
+    /// 矩形領域、`[from.0..to.0) x [from.1..to.1)`を指定の`Monoid`でfoldした演算結果
+    pub fn range(&self, from: (usize, usize), to: (usize, usize)) -> T {
+        let mut res = T::identity();
+        let (mut l, mut r) = (from.0 + self.height(), to.0 + self.height());
+        while l < r {
+            if l & 1 == 1 {
+                res = T::op(&res, &self.range_row(l, from.1, to.1));
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                res = T::op(&res, &self.range_row(r, from.1, to.1));
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        res
+    }
+}
+
+#[test]
+fn segment_tree_2d_test() {
+    monoid_def! {
+        Max<i64>,
+        std::i64::MIN,
+        |x: i64, y: i64| x.max(y)
+    }
+
+    let v = vec![
+        vec![3, 1, 4, 1, 5],
+        vec![9, 2, 6, 5, 3],
+        vec![5, 8, 9, 7, 9],
+    ];
+    let s = SegmentTree2D::<Max>::new(&v);
+
+    assert_eq!(s.range((0, 0), (3, 5)).0, 9);
+    assert_eq!(s.range((0, 1), (2, 4)).0, 6);
+    assert_eq!(s.range((2, 0), (3, 2)).0, 8);
+    assert_eq!(s.range((0, 2), (1, 3)).0, 4);
+    assert_eq!(s.range((1, 1), (1, 4)).0, std::i64::MIN);
+    assert_eq!(s.get(1, 3).unwrap().0, 5);
+    assert_eq!(s.get(4, 0), None);
+}
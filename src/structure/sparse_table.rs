@@ -0,0 +1,181 @@
+use super::algebraic_traits::idempotent::Idempotent;
+use super::algebraic_traits::monoid::Monoid;
+
+use cargo_snippet::snippet;
+
+/// `floor(log2(n))`
+#[snippet("sparse_table")]
+fn log2_floor(n: usize) -> usize {
+    std::mem::size_of::<usize>() * 8 - 1 - n.leading_zeros() as usize
+}
+
+/// 静的な配列に対して冪等な演算の区間foldを`O(1)`で求める
+///
+/// 構築 `O(n log n)`
+/// 区間 `O(1)`
+/// 更新は作り直す以外に出来ない
+#[snippet("sparse_table")]
+#[derive(Debug, Clone)]
+pub struct SparseTable<T: Idempotent> {
+    /// `table[k][i]` => `[i..i + 2^k)`のfold
+    table: Vec<Vec<T>>,
+}
+
+#[snippet("sparse_table")]
+impl<T: Idempotent + Copy> SparseTable<T> {
+    pub fn new<I: Into<T> + Copy>(v: &[I]) -> Self {
+        let n = v.len();
+        let mut table = vec![v.iter().map(|&x| x.into()).collect::<Vec<T>>()];
+        let mut k = 1;
+        while (1 << k) <= n {
+            let prev = &table[k - 1];
+            let row = (0..=n - (1 << k))
+                .map(|i| T::op(&prev[i], &prev[i + (1 << (k - 1))]))
+                .collect();
+            table.push(row);
+            k += 1;
+        }
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 区間、`[from..to)`を指定の`Monoid`でfoldした演算結果
+    ///
+    /// `O(1)`
+    pub fn range(&self, from: usize, to: usize) -> T {
+        if from >= to {
+            return T::identity();
+        }
+        let k = log2_floor(to - from);
+        T::op(&self.table[k][from], &self.table[k][to - (1 << k)])
+    }
+}
+
+/// 静的な配列に対して任意のMonoidの区間foldを`O(1)`で求める
+///
+/// 構築 `O(n log n)`
+/// 区間 `O(1)`
+#[snippet("sparse_table")]
+#[derive(Debug, Clone)]
+pub struct DisjointSparseTable<T: Monoid> {
+    /// `table[k]`は長さ`2^(k + 1)`のブロックごとに、
+    /// 中央から左へのfoldと中央から右へのfoldを持つ
+    table: Vec<Vec<T>>,
+}
+
+#[snippet("sparse_table")]
+impl<T: Monoid + Copy> DisjointSparseTable<T> {
+    pub fn new<I: Into<T> + Copy>(v: &[I]) -> Self {
+        let v = v.iter().map(|&x| x.into()).collect::<Vec<T>>();
+        let n = v.len();
+        let mut lg = 1;
+        while (1 << lg) < n {
+            lg += 1;
+        }
+
+        let mut table = vec![vec![T::identity(); n]; lg];
+        table[0] = v.clone();
+        for (k, row) in table.iter_mut().enumerate().skip(1) {
+            let shift = 1 << k;
+            for i in (0..n).step_by(shift << 1) {
+                let mid = std::cmp::min(i + shift, n);
+                row[mid - 1] = v[mid - 1];
+                for j in (i..mid - 1).rev() {
+                    row[j] = T::op(&v[j], &row[j + 1]);
+                }
+                if n <= mid {
+                    break;
+                }
+                row[mid] = v[mid];
+                for j in mid + 1..std::cmp::min(mid + shift, n) {
+                    row[j] = T::op(&row[j - 1], &v[j]);
+                }
+            }
+        }
+
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 区間、`[from..to)`を指定の`Monoid`でfoldした演算結果
+    ///
+    /// `O(1)`
+    pub fn range(&self, from: usize, to: usize) -> T {
+        if from >= to {
+            return T::identity();
+        }
+        let last = to - 1;
+        if from == last {
+            return self.table[0][from];
+        }
+        let k = log2_floor(from ^ last);
+        T::op(&self.table[k][from], &self.table[k][last])
+    }
+}
+
+#[test]
+fn sparse_table_test() {
+    monoid_def! {
+        Min<i64>,
+        std::i64::MAX,
+        |x: i64, y: i64| x.min(y)
+    }
+
+    impl Idempotent for Min {}
+
+    let v = vec![5i64, 3, 8, 6, 1, 9, 2, 7, 4];
+    let st = SparseTable::<Min>::new(&v);
+
+    for i in 0..v.len() {
+        for j in i + 1..=v.len() {
+            assert_eq!(st.range(i, j).0, *v[i..j].iter().min().unwrap());
+        }
+    }
+    assert_eq!(st.range(3, 3).0, std::i64::MAX);
+    assert_eq!(st.len(), v.len());
+    assert!(SparseTable::<Min>::new::<i64>(&[]).is_empty());
+}
+
+#[test]
+fn disjoint_sparse_table_test() {
+    // 非可換な演算でも順序が保たれることを確かめる
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Affine(i64, i64);
+
+    impl Monoid for Affine {
+        fn identity() -> Self {
+            Affine(1, 0)
+        }
+
+        fn op(x: &Self, y: &Self) -> Self {
+            Affine(x.0 * y.0 % 1_000_000_007, (x.1 * y.0 + y.1) % 1_000_000_007)
+        }
+    }
+
+    let v = (0..13).map(|i| Affine(i % 4 + 1, i)).collect::<Vec<_>>();
+    let dst = DisjointSparseTable::<Affine>::new(&v);
+
+    for i in 0..v.len() {
+        for j in i..=v.len() {
+            assert_eq!(dst.range(i, j), Affine::fold(&v[i..j]));
+        }
+    }
+
+    let one = DisjointSparseTable::<Affine>::new(&[Affine(2, 3)]);
+    assert_eq!(one.range(0, 1), Affine(2, 3));
+    assert!(!one.is_empty());
+}
@@ -31,7 +31,8 @@ mod visualize {
     impl_vis_for_sized! {
         usize, u8, u16, u32, u64, u128,
         isize, i8, i16, i32, i64, i128,
-        String, &str, char
+        String, &str, char,
+        crate::modint::ModInt
     }
 
     impl<T: std::fmt::Display> Visualize for [T] {